@@ -1,9 +1,81 @@
-use crate::Error;
+use unicode_segmentation::UnicodeSegmentation;
+use unicode_width::UnicodeWidthStr;
 
+use crate::{Error, Rect, Style};
+
+/// A single terminal cell holding one grapheme cluster.
+///
+/// A double-width grapheme occupies two cells, the second one is a continuation
+/// cell with an empty symbol that is never printed on its own.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Cell {
+    pub symbol: String,
+    pub style: Style,
+}
+
+impl Cell {
+    /// Number of columns the symbol occupies when printed, 0 for continuation cells.
+    pub fn width(&self) -> usize {
+        symbol_width(&self.symbol)
+    }
+
+    pub fn is_continuation(&self) -> bool {
+        self.symbol.is_empty()
+    }
+}
+
+impl Default for Cell {
+    fn default() -> Self {
+        Self {
+            symbol: " ".to_string(),
+            style: Style::default(),
+        }
+    }
+}
+
+/// The display width of a grapheme cluster, clamped to the 0-2 columns a cell can represent.
+pub fn symbol_width(symbol: &str) -> usize {
+    symbol.width().min(2)
+}
+
+/// The display width of a string, i.e. the number of cells [`Buffer::draw_str`] uses for it.
+pub fn str_width(content: &str) -> usize {
+    content.graphemes(true).map(symbol_width).sum()
+}
+
+/// A grid of cells that elements draw into.
+///
+/// Coordinates are relative to an origin, the top left corner of the buffer
+/// unless moved with [`Buffer::push_viewport`], which lets elements draw their
+/// children in their own coordinate space.
+///
+/// Drawing is restricted to a clip area, which is the whole buffer unless
+/// narrowed with [`Buffer::push_clip`]. Anything drawn outside of it is silently
+/// discarded, unless the buffer is in strict mode in which case an error is returned.
 pub struct Buffer {
     width: usize,
     height: usize,
-    inner: Vec<char>,
+    inner: Vec<Cell>,
+    frames: Vec<Frame>,
+    strict: bool,
+    cursor: Option<(usize, usize)>,
+}
+
+/// A clip area and an origin, both in absolute coordinates. The origin is
+/// negative when scrolled past the top left corner of the buffer.
+#[derive(Debug, Clone, Copy)]
+struct Frame {
+    clip: Rect,
+    origin: (isize, isize),
+}
+
+impl Frame {
+    /// Converts a position relative to the origin to an absolute one, if it is inside the buffer's coordinate space.
+    fn absolute(&self, x: usize, y: usize) -> Option<(usize, usize)> {
+        let x = usize::try_from(self.origin.0 + x as isize).ok()?;
+        let y = usize::try_from(self.origin.1 + y as isize).ok()?;
+        Some((x, y))
+    }
 }
 
 impl Buffer {
@@ -11,55 +83,281 @@ impl Buffer {
         Buffer {
             width,
             height,
-            inner: vec![' '; width * height],
+            inner: vec![Cell::default(); width * height],
+            frames: Vec::new(),
+            strict: false,
+            cursor: None,
+        }
+    }
+
+    /// The whole area of the buffer.
+    pub fn area(&self) -> Rect {
+        Rect::new(0, 0, self.width, self.height)
+    }
+
+    fn frame(&self) -> Frame {
+        self.frames.last().copied().unwrap_or(Frame {
+            clip: self.area(),
+            origin: (0, 0),
+        })
+    }
+
+    /// The area drawing is currently restricted to, relative to the origin.
+    pub fn clip(&self) -> Rect {
+        let Frame { clip, origin } = self.frame();
+        // The clip area never starts before the origin.
+        Rect::new(
+            (clip.x as isize - origin.0) as usize,
+            (clip.y as isize - origin.1) as usize,
+            clip.width,
+            clip.height,
+        )
+    }
+
+    /// The current origin in absolute coordinates.
+    pub fn origin(&self) -> (isize, isize) {
+        self.frame().origin
+    }
+
+    /// Restricts drawing to `rect`, within the current clip area, until the matching [`Buffer::pop_clip`].
+    pub fn push_clip(&mut self, rect: Rect) {
+        let frame = self.frame();
+        let x = frame.origin.0 + rect.x as isize;
+        let y = frame.origin.1 + rect.y as isize;
+        // Parts before the top left corner of the buffer are cut off.
+        let rect = Rect::new(
+            x.max(0) as usize,
+            y.max(0) as usize,
+            rect.width.saturating_sub(x.min(0).unsigned_abs()),
+            rect.height.saturating_sub(y.min(0).unsigned_abs()),
+        );
+        self.frames.push(Frame {
+            clip: frame.clip.intersection(&rect),
+            origin: frame.origin,
+        });
+    }
+
+    pub fn pop_clip(&mut self) {
+        self.frames.pop();
+    }
+
+    /// Moves the origin to the top left corner of `rect` and restricts drawing to it,
+    /// until the matching [`Buffer::pop_viewport`].
+    pub fn push_viewport(&mut self, rect: Rect) {
+        self.push_clip(rect);
+        if let Some(frame) = self.frames.last_mut() {
+            frame.origin.0 += rect.x as isize;
+            frame.origin.1 += rect.y as isize;
         }
     }
 
+    /// Moves the origin `x` columns to the left and `y` rows up, keeping the clip
+    /// area, until the matching [`Buffer::pop_viewport`]. Used to scroll content.
+    pub fn push_scroll(&mut self, x: usize, y: usize) {
+        let mut frame = self.frame();
+        frame.origin.0 -= x as isize;
+        frame.origin.1 -= y as isize;
+        self.frames.push(frame);
+    }
+
+    pub fn pop_viewport(&mut self) {
+        self.frames.pop();
+    }
+
+    /// In strict mode drawing outside of the buffer or of the clip area returns
+    /// an error instead of being discarded, which helps to find layout bugs.
+    pub fn set_strict(&mut self, strict: bool) {
+        self.strict = strict;
+    }
+
+    pub fn is_strict(&self) -> bool {
+        self.strict
+    }
+
+    /// Asks for the terminal cursor to be shown at (x, y), e.g. at the edit point
+    /// of a text input. Positions outside of the clip area hide the cursor.
+    pub fn set_cursor(&mut self, x: usize, y: usize) {
+        let frame = self.frame();
+        self.cursor = frame
+            .absolute(x, y)
+            .filter(|&(x, y)| frame.clip.contains(x, y));
+    }
+
+    /// The absolute position the terminal cursor should be shown at, if any.
+    pub fn cursor(&self) -> Option<(usize, usize)> {
+        self.cursor
+    }
+
+    /// Resets every cell, the clip area, the origin and the cursor.
     pub fn clear(&mut self) {
+        self.frames.clear();
+        self.cursor = None;
+        let blank = Cell::default();
         for cell in self.inner.iter_mut() {
-            if *cell != ' ' {
-                *cell = ' ';
+            if *cell != blank {
+                *cell = blank.clone();
             }
         }
     }
 
     pub fn draw_char(&mut self, x: usize, y: usize, c: char) -> Result<(), Error> {
-        if x >= self.width || y >= self.height {
+        self.draw_char_styled(x, y, c, Style::default())
+    }
+
+    pub fn draw_char_styled(
+        &mut self,
+        x: usize,
+        y: usize,
+        c: char,
+        style: Style,
+    ) -> Result<(), Error> {
+        self.draw_grapheme(x, y, c.encode_utf8(&mut [0; 4]), style)
+    }
+
+    /// Draws a single grapheme cluster, a double-width one also takes the cell to its right.
+    /// Zero-width graphemes are ignored.
+    pub fn draw_grapheme(
+        &mut self,
+        x: usize,
+        y: usize,
+        grapheme: &str,
+        style: Style,
+    ) -> Result<(), Error> {
+        let width = symbol_width(grapheme);
+        if width == 0 {
+            return Ok(());
+        }
+
+        let Some(index) = self.check(x, y, width)? else {
+            return Ok(());
+        };
+        for i in index..=index + width - 1 {
+            self.detach(i);
+        }
+
+        let cell = Cell {
+            symbol: grapheme.to_string(),
+            style,
+        };
+        if self.inner[index] != cell {
+            self.inner[index] = cell;
+        };
+        if width == 2 {
+            self.inner[index + 1] = Cell {
+                symbol: String::new(),
+                style,
+            };
+        }
+
+        Ok(())
+    }
+
+    /// Draws `content` on a single row starting at (x, y) and returns the number of columns used.
+    pub fn draw_str(
+        &mut self,
+        x: usize,
+        y: usize,
+        content: &str,
+        style: Style,
+    ) -> Result<usize, Error> {
+        // Validate up front, so that nothing is drawn if the content doesn't fit.
+        if self.strict {
+            let mut offset = 0;
+            for grapheme in content.graphemes(true) {
+                let width = symbol_width(grapheme);
+                if width > 0 {
+                    self.check(x + offset, y, width)?;
+                }
+                offset += width;
+            }
+        }
+
+        let mut offset = 0;
+        for grapheme in content.graphemes(true) {
+            self.draw_grapheme(x + offset, y, grapheme, style)?;
+            offset += symbol_width(grapheme);
+        }
+        Ok(offset)
+    }
+
+    /// Returns the index of the cell a glyph `width` columns wide is drawn at when
+    /// given (x, y) relative to the origin, `None` if it can't be drawn there, or an
+    /// error in strict mode if it can't. Errors report absolute coordinates.
+    /// Positions scrolled past the top left corner of the buffer are always clipped.
+    fn check(&self, x: usize, y: usize, width: usize) -> Result<Option<usize>, Error> {
+        let frame = self.frame();
+        let clip = frame.clip;
+        let Some((x, y)) = frame.absolute(x, y) else {
+            return Ok(None);
+        };
+        let last_x = x + width - 1;
+        if last_x >= self.width || y >= self.height {
+            if !self.strict {
+                return Ok(None);
+            }
             return Err(Error::PositionError {
-                x,
+                x: last_x,
                 y,
                 width: self.width,
                 height: self.height,
             });
         };
 
-        let index = (y * self.width) + x;
-        if self.inner[index] != c {
-            self.inner[index] = c;
-        };
+        if !clip.contains(x, y) || !clip.contains(last_x, y) {
+            if !self.strict {
+                return Ok(None);
+            }
+            return Err(Error::ClipError {
+                x: if clip.contains(x, y) { last_x } else { x },
+                y,
+                clip,
+            });
+        }
 
-        Ok(())
+        Ok(Some((y * self.width) + x))
+    }
+
+    /// Breaks up the double-width glyph that the cell at `index` is part of, if any,
+    /// so that overwriting half of it does not leave the other half behind.
+    fn detach(&mut self, index: usize) {
+        let row_start = index - index % self.width;
+        if self.inner[index].is_continuation() && index > row_start {
+            self.inner[index - 1].symbol = " ".to_string();
+            self.inner[index].symbol = " ".to_string();
+        } else if self.inner[index].width() == 2 && index + 1 < row_start + self.width {
+            self.inner[index + 1].symbol = " ".to_string();
+        }
     }
 
-    pub fn iter(&self) -> BufferIter {
+    pub fn iter(&self) -> BufferIter<'_> {
         BufferIter {
             index: 0,
             inner: &self.inner,
         }
     }
 
+    pub fn get_cell(&self, x: usize, y: usize) -> Option<&Cell> {
+        if x >= self.width || y >= self.height {
+            return None;
+        }
+        self.inner.get((y * self.width) + x)
+    }
+
     // Retrieve the buffer content, only for testing
     pub fn get_content(&self) -> Vec<char> {
-        self.inner.clone()
+        self.inner
+            .iter()
+            .map(|cell| cell.symbol.chars().next().unwrap_or(' '))
+            .collect()
     }
 }
 
 pub struct BufferIter<'a> {
     index: usize,
-    inner: &'a Vec<char>,
+    inner: &'a Vec<Cell>,
 }
 impl<'a> Iterator for BufferIter<'a> {
-    type Item = &'a char;
+    type Item = &'a Cell;
 
     fn next(&mut self) -> Option<Self::Item> {
         let item = self.inner.get(self.index);
@@ -71,13 +369,20 @@ impl<'a> Iterator for BufferIter<'a> {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::Color;
+
+    fn symbols(buffer: &Buffer, y: usize) -> Vec<&str> {
+        (0..buffer.width)
+            .map(|x| buffer.get_cell(x, y).unwrap().symbol.as_str())
+            .collect()
+    }
 
     #[test]
     fn test_buffer_initialization() {
         let buffer = Buffer::new(5, 3);
         let mut count = 0;
-        for c in buffer.iter() {
-            if *c == ' ' {
+        for cell in buffer.iter() {
+            if cell.symbol == " " {
                 count += 1
             }
         }
@@ -88,18 +393,217 @@ mod tests {
     fn test_draw_char() -> Result<(), Error> {
         let mut buffer = Buffer::new(5, 3);
         buffer.draw_char(1, 1, 'X')?;
-        assert_eq!(buffer.inner[6], 'X');
+        assert_eq!(buffer.inner[6].symbol, "X");
+        Ok(())
+    }
+
+    #[test]
+    fn test_draw_char_styled() -> Result<(), Error> {
+        let mut buffer = Buffer::new(5, 3);
+        let style = Style::new().fg(Color::Red);
+        buffer.draw_char_styled(1, 1, 'X', style)?;
+        let cell = buffer.get_cell(1, 1).unwrap();
+        assert_eq!(cell.symbol, "X");
+        assert_eq!(cell.style, style);
         Ok(())
     }
 
     #[test]
     fn test_clear_buffer() -> Result<(), Error> {
         let mut buffer = Buffer::new(10, 5);
-        buffer.draw_char(1, 1, 'X')?;
+        buffer.draw_char_styled(1, 1, 'X', Style::new().bg(Color::Blue))?;
         buffer.clear();
         for cell in buffer.inner.iter() {
-            assert_eq!(*cell, ' ');
+            assert_eq!(*cell, Cell::default());
         }
         Ok(())
     }
+
+    #[test]
+    fn test_str_width() {
+        assert_eq!(str_width("abc"), 3);
+        assert_eq!(str_width("日本"), 4);
+        assert_eq!(str_width("e\u{301}"), 1);
+        assert_eq!(str_width("👍🏽"), 2);
+    }
+
+    #[test]
+    fn test_draw_wide_char() -> Result<(), Error> {
+        let mut buffer = Buffer::new(5, 1);
+        let used = buffer.draw_str(0, 0, "a日b", Style::default())?;
+        assert_eq!(used, 4);
+        assert_eq!(symbols(&buffer, 0), vec!["a", "日", "", "b", " "]);
+        assert!(buffer.get_cell(2, 0).unwrap().is_continuation());
+        Ok(())
+    }
+
+    #[test]
+    fn test_draw_grapheme_cluster() -> Result<(), Error> {
+        let mut buffer = Buffer::new(5, 1);
+        let used = buffer.draw_str(0, 0, "e\u{301}x", Style::default())?;
+        assert_eq!(used, 2);
+        assert_eq!(symbols(&buffer, 0), vec!["e\u{301}", "x", " ", " ", " "]);
+        Ok(())
+    }
+
+    #[test]
+    fn test_overwrite_half_of_wide_char() -> Result<(), Error> {
+        let mut buffer = Buffer::new(4, 1);
+        buffer.draw_str(0, 0, "日本", Style::default())?;
+        buffer.draw_char(1, 0, 'x')?;
+        assert_eq!(symbols(&buffer, 0), vec![" ", "x", "本", ""]);
+
+        buffer.draw_char(2, 0, 'y')?;
+        assert_eq!(symbols(&buffer, 0), vec![" ", "x", "y", " "]);
+        Ok(())
+    }
+
+    #[test]
+    fn test_wide_char_over_narrow_chars() -> Result<(), Error> {
+        let mut buffer = Buffer::new(4, 1);
+        buffer.draw_str(0, 0, "本", Style::default())?;
+        buffer.draw_str(1, 0, "日", Style::default())?;
+        assert_eq!(symbols(&buffer, 0), vec![" ", "日", "", " "]);
+        Ok(())
+    }
+
+    #[test]
+    fn test_wide_char_does_not_fit() -> Result<(), Error> {
+        let mut buffer = Buffer::new(3, 1);
+        buffer.draw_str(2, 0, "日", Style::default())?;
+        assert_eq!(symbols(&buffer, 0), vec![" ", " ", " "]);
+
+        buffer.set_strict(true);
+        assert!(buffer.draw_str(2, 0, "日", Style::default()).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_draw_outside_is_discarded() -> Result<(), Error> {
+        let mut buffer = Buffer::new(3, 2);
+        buffer.draw_char(5, 0, 'x')?;
+        let used = buffer.draw_str(1, 1, "abc", Style::default())?;
+        assert_eq!(used, 3);
+        assert_eq!(symbols(&buffer, 1), vec![" ", "a", "b"]);
+        Ok(())
+    }
+
+    #[test]
+    fn test_strict_mode() {
+        let mut buffer = Buffer::new(3, 2);
+        buffer.set_strict(true);
+        assert!(matches!(
+            buffer.draw_char(5, 0, 'x'),
+            Err(Error::PositionError { x: 5, y: 0, .. })
+        ));
+        // Nothing is drawn when the string doesn't fit.
+        assert!(buffer.draw_str(1, 1, "abc", Style::default()).is_err());
+        assert_eq!(symbols(&buffer, 1), vec![" ", " ", " "]);
+    }
+
+    #[test]
+    fn test_clip() -> Result<(), Error> {
+        let mut buffer = Buffer::new(5, 1);
+        buffer.push_clip(Rect::new(1, 0, 3, 1));
+        buffer.draw_str(0, 0, "abcde", Style::default())?;
+        assert_eq!(symbols(&buffer, 0), vec![" ", "b", "c", "d", " "]);
+
+        // Nested clips can only narrow the area.
+        buffer.push_clip(Rect::new(3, 0, 5, 1));
+        assert_eq!(buffer.clip(), Rect::new(3, 0, 1, 1));
+        buffer.pop_clip();
+        assert_eq!(buffer.clip(), Rect::new(1, 0, 3, 1));
+        buffer.pop_clip();
+        assert_eq!(buffer.clip(), buffer.area());
+        Ok(())
+    }
+
+    #[test]
+    fn test_clip_strict() {
+        let mut buffer = Buffer::new(5, 1);
+        buffer.set_strict(true);
+        buffer.push_clip(Rect::new(1, 0, 3, 1));
+        assert!(matches!(
+            buffer.draw_char(4, 0, 'x'),
+            Err(Error::ClipError { x: 4, y: 0, .. })
+        ));
+        assert!(buffer.draw_char(3, 0, 'x').is_ok());
+    }
+
+    #[test]
+    fn test_viewport() -> Result<(), Error> {
+        let mut buffer = Buffer::new(6, 3);
+        buffer.push_viewport(Rect::new(1, 1, 4, 2));
+        assert_eq!(buffer.origin(), (1, 1));
+        assert_eq!(buffer.clip(), Rect::new(0, 0, 4, 2));
+
+        buffer.push_viewport(Rect::new(2, 1, 5, 5));
+        assert_eq!(buffer.origin(), (3, 2));
+        assert_eq!(buffer.clip(), Rect::new(0, 0, 2, 1));
+        buffer.draw_str(0, 0, "abc", Style::default())?;
+        buffer.pop_viewport();
+
+        buffer.draw_char(0, 0, 'x')?;
+        buffer.pop_viewport();
+
+        assert_eq!(symbols(&buffer, 1), vec![" ", "x", " ", " ", " ", " "]);
+        assert_eq!(symbols(&buffer, 2), vec![" ", " ", " ", "a", "b", " "]);
+        assert_eq!(buffer.origin(), (0, 0));
+        Ok(())
+    }
+
+    #[test]
+    fn test_scroll() -> Result<(), Error> {
+        let mut buffer = Buffer::new(4, 2);
+        buffer.push_viewport(Rect::new(1, 0, 2, 2));
+        buffer.push_scroll(3, 1);
+        assert_eq!(buffer.origin(), (-2, -1));
+        assert_eq!(buffer.clip(), Rect::new(3, 1, 2, 2));
+
+        buffer.draw_str(0, 0, "abcdef", Style::default())?;
+        buffer.draw_str(0, 1, "ghijkl", Style::default())?;
+        buffer.push_clip(Rect::new(0, 0, 4, 2));
+        assert_eq!(buffer.clip(), Rect::new(3, 1, 1, 1));
+        buffer.pop_clip();
+        buffer.pop_viewport();
+        buffer.pop_viewport();
+
+        assert_eq!(symbols(&buffer, 0), vec![" ", "j", "k", " "]);
+        assert_eq!(symbols(&buffer, 1), vec![" "; 4]);
+        Ok(())
+    }
+
+    #[test]
+    fn test_viewport_strict_reports_absolute_position() {
+        let mut buffer = Buffer::new(6, 3);
+        buffer.set_strict(true);
+        buffer.push_viewport(Rect::new(1, 1, 2, 1));
+        let result = buffer.draw_str(0, 0, "abc", Style::default());
+        assert!(matches!(result, Err(Error::ClipError { x: 3, y: 1, .. })));
+    }
+
+    #[test]
+    fn test_clear_resets_clip() {
+        let mut buffer = Buffer::new(5, 1);
+        buffer.push_clip(Rect::new(1, 0, 1, 1));
+        buffer.clear();
+        assert_eq!(buffer.clip(), buffer.area());
+    }
+
+    #[test]
+    fn test_cursor() {
+        let mut buffer = Buffer::new(6, 3);
+        assert_eq!(buffer.cursor(), None);
+
+        buffer.push_viewport(Rect::new(2, 1, 3, 2));
+        buffer.set_cursor(1, 1);
+        assert_eq!(buffer.cursor(), Some((3, 2)));
+        buffer.set_cursor(3, 0);
+        assert_eq!(buffer.cursor(), None);
+        buffer.set_cursor(0, 0);
+        buffer.pop_viewport();
+
+        buffer.clear();
+        assert_eq!(buffer.cursor(), None);
+    }
 }
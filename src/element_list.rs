@@ -0,0 +1,193 @@
+use std::{cell::RefCell, rc::Rc};
+
+use crate::{Buffer, Error, Rect, UIElement};
+
+/// Elements identified by id, kept in drawing order.
+///
+/// The first element is drawn first, i.e. it is at the bottom, and the last one
+/// is drawn on top of all the others. New elements are added on top.
+#[derive(Default)]
+pub struct ElementList {
+    entries: Vec<(String, Rc<RefCell<dyn UIElement>>)>,
+}
+
+impl ElementList {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds an element on top of the others. An element with the same id is
+    /// replaced in place, keeping its position in the drawing order, and returned.
+    pub fn insert(
+        &mut self,
+        id: String,
+        element: Rc<RefCell<dyn UIElement>>,
+    ) -> Option<Rc<RefCell<dyn UIElement>>> {
+        match self.position(&id) {
+            Some(index) => Some(std::mem::replace(&mut self.entries[index].1, element)),
+            None => {
+                self.entries.push((id, element));
+                None
+            }
+        }
+    }
+
+    pub fn remove(&mut self, id: &str) -> Option<Rc<RefCell<dyn UIElement>>> {
+        let index = self.position(id)?;
+        Some(self.entries.remove(index).1)
+    }
+
+    pub fn get(&self, id: &str) -> Option<&Rc<RefCell<dyn UIElement>>> {
+        let index = self.position(id)?;
+        Some(&self.entries[index].1)
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Iterates from the bottom-most to the top-most element.
+    pub fn iter(&self) -> impl DoubleEndedIterator<Item = (&String, &Rc<RefCell<dyn UIElement>>)> {
+        self.entries.iter().map(|(id, element)| (id, element))
+    }
+
+    /// Iterates from the bottom-most to the top-most element.
+    pub fn values(&self) -> impl DoubleEndedIterator<Item = &Rc<RefCell<dyn UIElement>>> {
+        self.entries.iter().map(|(_, element)| element)
+    }
+
+    /// Iterates over the ids from the bottom-most to the top-most element.
+    pub fn ids(&self) -> impl DoubleEndedIterator<Item = &String> {
+        self.entries.iter().map(|(id, _)| id)
+    }
+
+    /// The position in the drawing order, 0 being the bottom.
+    pub fn z_index(&self, id: &str) -> Option<usize> {
+        self.position(id)
+    }
+
+    /// Moves the element one step up. Returns false if there is no such element.
+    pub fn raise(&mut self, id: &str) -> bool {
+        let Some(index) = self.position(id) else {
+            return false;
+        };
+        if index + 1 < self.entries.len() {
+            self.entries.swap(index, index + 1);
+        }
+        true
+    }
+
+    /// Moves the element one step down. Returns false if there is no such element.
+    pub fn lower(&mut self, id: &str) -> bool {
+        let Some(index) = self.position(id) else {
+            return false;
+        };
+        if index > 0 {
+            self.entries.swap(index, index - 1);
+        }
+        true
+    }
+
+    /// Moves the element on top of all the others. Returns false if there is no such element.
+    pub fn bring_to_front(&mut self, id: &str) -> bool {
+        let Some(index) = self.position(id) else {
+            return false;
+        };
+        let entry = self.entries.remove(index);
+        self.entries.push(entry);
+        true
+    }
+
+    /// Moves the element below all the others. Returns false if there is no such element.
+    pub fn send_to_back(&mut self, id: &str) -> bool {
+        let Some(index) = self.position(id) else {
+            return false;
+        };
+        let entry = self.entries.remove(index);
+        self.entries.insert(0, entry);
+        true
+    }
+
+    /// Draws the elements from the bottom-most to the top-most one, as the sub
+    /// elements of a parent with the given bounds: they are positioned relative to
+    /// the parent and clipped to its bounds.
+    pub fn draw(&self, buffer: &mut Buffer, bounds: Rect) -> Result<(), Error> {
+        buffer.push_viewport(bounds);
+        let result = self
+            .values()
+            .try_for_each(|element| element.borrow().draw(buffer));
+        buffer.pop_viewport();
+        result
+    }
+
+    fn position(&self, id: &str) -> Option<usize> {
+        self.entries.iter().position(|(entry_id, _)| entry_id == id)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Text;
+
+    fn list(ids: &[&str]) -> ElementList {
+        let mut list = ElementList::new();
+        for id in ids {
+            list.insert(id.to_string(), Rc::new(RefCell::new(Text::new(0, 0, id))));
+        }
+        list
+    }
+
+    fn order(list: &ElementList) -> Vec<&str> {
+        list.ids().map(|id| id.as_str()).collect()
+    }
+
+    #[test]
+    fn test_insertion_order() {
+        let list = list(&["a", "b", "c"]);
+        assert_eq!(order(&list), vec!["a", "b", "c"]);
+        assert_eq!(list.z_index("c"), Some(2));
+    }
+
+    #[test]
+    fn test_replace_keeps_position() {
+        let mut list = list(&["a", "b", "c"]);
+        let old = list.insert("a".to_string(), Rc::new(RefCell::new(Text::new(0, 0, "x"))));
+        assert!(old.is_some());
+        assert_eq!(order(&list), vec!["a", "b", "c"]);
+    }
+
+    #[test]
+    fn test_remove() {
+        let mut list = list(&["a", "b", "c"]);
+        assert!(list.remove("b").is_some());
+        assert!(list.remove("b").is_none());
+        assert_eq!(order(&list), vec!["a", "c"]);
+    }
+
+    #[test]
+    fn test_raise_and_lower() {
+        let mut list = list(&["a", "b", "c"]);
+        assert!(list.raise("a"));
+        assert_eq!(order(&list), vec!["b", "a", "c"]);
+        assert!(list.raise("c"));
+        assert_eq!(order(&list), vec!["b", "a", "c"]);
+        assert!(list.lower("c"));
+        assert_eq!(order(&list), vec!["b", "c", "a"]);
+        assert!(!list.lower("d"));
+    }
+
+    #[test]
+    fn test_front_and_back() {
+        let mut list = list(&["a", "b", "c"]);
+        assert!(list.bring_to_front("a"));
+        assert_eq!(order(&list), vec!["b", "c", "a"]);
+        assert!(list.send_to_back("c"));
+        assert_eq!(order(&list), vec!["c", "b", "a"]);
+        assert!(!list.bring_to_front("d"));
+    }
+}
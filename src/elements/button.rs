@@ -0,0 +1,214 @@
+use crate::{
+    buffer::str_width, Attributes, Buffer, Error, Event, MouseEventKind, Style, UIElement,
+};
+
+/// A button drawn as `[ label ]` that calls its `on_click` callback when activated
+/// with Enter, Space or a click.
+///
+/// A click activates the button when the mouse button is released over it, so
+/// dragging off the button before releasing cancels the click.
+pub struct Button {
+    x: usize,
+    y: usize,
+    label: String,
+    style: Style,
+    focus_style: Style,
+    pressed_style: Style,
+    focused: bool,
+    pressed: bool,
+    on_click: Option<Box<dyn FnMut()>>,
+}
+
+impl Button {
+    pub fn new(x: usize, y: usize, label: &str) -> Self {
+        Self {
+            x,
+            y,
+            label: label.to_string(),
+            style: Style::default(),
+            focus_style: Style::new().attributes(Attributes::REVERSE),
+            pressed_style: Style::new().attributes(Attributes::REVERSE | Attributes::BOLD),
+            focused: false,
+            pressed: false,
+            on_click: None,
+        }
+    }
+
+    pub fn style(mut self, style: Style) -> Self {
+        self.style = style;
+        self
+    }
+    /// The style used while the button has the focus.
+    pub fn focus_style(mut self, style: Style) -> Self {
+        self.focus_style = style;
+        self
+    }
+    /// The style used while the mouse button is held down on the button.
+    pub fn pressed_style(mut self, style: Style) -> Self {
+        self.pressed_style = style;
+        self
+    }
+    pub fn on_click(mut self, callback: impl FnMut() + 'static) -> Self {
+        self.on_click = Some(Box::new(callback));
+        self
+    }
+
+    pub fn set_label(&mut self, label: &str) {
+        self.label = label.to_string();
+    }
+
+    pub fn is_focused(&self) -> bool {
+        self.focused
+    }
+    pub fn is_pressed(&self) -> bool {
+        self.pressed
+    }
+
+    /// Calls the `on_click` callback.
+    pub fn click(&mut self) {
+        if let Some(on_click) = self.on_click.as_mut() {
+            on_click();
+        }
+    }
+}
+
+impl UIElement for Button {
+    fn draw(&self, buffer: &mut Buffer) -> Result<(), Error> {
+        let style = if self.pressed {
+            self.style.patch(self.pressed_style)
+        } else if self.focused {
+            self.style.patch(self.focus_style)
+        } else {
+            self.style
+        };
+        buffer.draw_str(self.x, self.y, &format!("[ {} ]", self.label), style)?;
+        Ok(())
+    }
+    fn set_position(&mut self, x: usize, y: usize) {
+        self.x = x;
+        self.y = y;
+    }
+    fn get_position(&self) -> (usize, usize) {
+        (self.x, self.y)
+    }
+    fn preferred_size(&self) -> (usize, usize) {
+        (str_width(&self.label) + 4, 1)
+    }
+    fn is_focusable(&self) -> bool {
+        true
+    }
+    fn set_focused(&mut self, focused: bool) {
+        self.focused = focused;
+        if !focused {
+            self.pressed = false;
+        }
+    }
+    fn handle_event(&mut self, event: &Event) -> bool {
+        if event.is_activation() {
+            self.click();
+            return true;
+        }
+        if event.is_left_click() {
+            self.pressed = true;
+            return true;
+        }
+        if let Event::Mouse {
+            kind: MouseEventKind::Release,
+            x,
+            y,
+            ..
+        } = *event
+        {
+            if self.pressed {
+                self.pressed = false;
+                let width = self.preferred_size().0 as isize;
+                if (0..width).contains(&x) && y == 0 {
+                    self.click();
+                }
+                return true;
+            }
+        }
+        false
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{KeyCode, KeyModifiers, MouseButton};
+    use std::{cell::Cell, rc::Rc};
+
+    fn counted() -> (Button, Rc<Cell<usize>>) {
+        let clicks = Rc::new(Cell::new(0));
+        let counter = clicks.clone();
+        let button = Button::new(0, 0, "OK").on_click(move || counter.set(counter.get() + 1));
+        (button, clicks)
+    }
+
+    #[test]
+    fn test_keys_activate() {
+        let (mut button, clicks) = counted();
+        assert!(button.handle_event(&Event::key(KeyCode::Enter, KeyModifiers::NONE)));
+        assert!(button.handle_event(&Event::key(KeyCode::Char(' '), KeyModifiers::NONE)));
+        assert!(!button.handle_event(&Event::key(KeyCode::Char('a'), KeyModifiers::NONE)));
+        assert_eq!(clicks.get(), 2);
+    }
+
+    #[test]
+    fn test_click_activates_on_release() {
+        let (mut button, clicks) = counted();
+        assert!(button.handle_event(&Event::mouse(
+            MouseEventKind::Press,
+            MouseButton::Left,
+            0,
+            0
+        )));
+        assert!(button.is_pressed());
+        assert_eq!(clicks.get(), 0);
+
+        assert!(button.handle_event(&Event::mouse(
+            MouseEventKind::Release,
+            MouseButton::None,
+            0,
+            0
+        )));
+        assert!(!button.is_pressed());
+        assert_eq!(clicks.get(), 1);
+
+        // A release without a press first is not a click.
+        assert!(!button.handle_event(&Event::mouse(
+            MouseEventKind::Release,
+            MouseButton::None,
+            0,
+            0
+        )));
+        assert_eq!(clicks.get(), 1);
+    }
+
+    #[test]
+    fn test_draw_states() -> Result<(), Error> {
+        let (mut button, _) = counted();
+        assert_eq!(button.get_size(), (6, 1));
+
+        let mut buffer = Buffer::new(6, 1);
+        button.draw(&mut buffer)?;
+        assert_eq!(buffer.get_content().iter().collect::<String>(), "[ OK ]");
+        assert_eq!(buffer.get_cell(2, 0).unwrap().style, Style::default());
+
+        button.set_focused(true);
+        button.draw(&mut buffer)?;
+        let focused = buffer.get_cell(2, 0).unwrap().style;
+        assert!(focused.attributes.contains(Attributes::REVERSE));
+
+        button.handle_event(&Event::mouse(
+            MouseEventKind::Press,
+            MouseButton::Left,
+            0,
+            0,
+        ));
+        button.draw(&mut buffer)?;
+        let pressed = buffer.get_cell(2, 0).unwrap().style;
+        assert!(pressed.attributes.contains(Attributes::BOLD));
+        Ok(())
+    }
+}
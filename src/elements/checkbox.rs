@@ -0,0 +1,152 @@
+use crate::{buffer::str_width, Attributes, Buffer, Error, Event, Style, UIElement};
+
+/// A checkbox drawn as `[x] label`, toggled with Enter, Space or a click.
+pub struct Checkbox {
+    x: usize,
+    y: usize,
+    label: String,
+    checked: bool,
+    style: Style,
+    focus_style: Style,
+    focused: bool,
+    on_change: Option<Box<dyn FnMut(bool)>>,
+}
+
+impl Checkbox {
+    pub fn new(x: usize, y: usize, label: &str) -> Self {
+        Self {
+            x,
+            y,
+            label: label.to_string(),
+            checked: false,
+            style: Style::default(),
+            focus_style: Style::new().attributes(Attributes::REVERSE),
+            focused: false,
+            on_change: None,
+        }
+    }
+
+    pub fn checked(mut self, checked: bool) -> Self {
+        self.checked = checked;
+        self
+    }
+    pub fn style(mut self, style: Style) -> Self {
+        self.style = style;
+        self
+    }
+    /// The style of the box while the checkbox has the focus.
+    pub fn focus_style(mut self, style: Style) -> Self {
+        self.focus_style = style;
+        self
+    }
+    /// Called with the new state whenever the checkbox is toggled by an event.
+    pub fn on_change(mut self, callback: impl FnMut(bool) + 'static) -> Self {
+        self.on_change = Some(Box::new(callback));
+        self
+    }
+
+    pub fn is_checked(&self) -> bool {
+        self.checked
+    }
+    /// Changes the state without calling `on_change`.
+    pub fn set_checked(&mut self, checked: bool) {
+        self.checked = checked;
+    }
+
+    pub fn is_focused(&self) -> bool {
+        self.focused
+    }
+
+    pub fn toggle(&mut self) {
+        self.checked = !self.checked;
+        if let Some(on_change) = self.on_change.as_mut() {
+            on_change(self.checked);
+        }
+    }
+}
+
+impl UIElement for Checkbox {
+    fn draw(&self, buffer: &mut Buffer) -> Result<(), Error> {
+        let mark = if self.checked { "[x]" } else { "[ ]" };
+        let style = if self.focused {
+            self.style.patch(self.focus_style)
+        } else {
+            self.style
+        };
+        buffer.draw_str(self.x, self.y, mark, style)?;
+        buffer.draw_str(self.x + 4, self.y, &self.label, self.style)?;
+        Ok(())
+    }
+    fn set_position(&mut self, x: usize, y: usize) {
+        self.x = x;
+        self.y = y;
+    }
+    fn get_position(&self) -> (usize, usize) {
+        (self.x, self.y)
+    }
+    fn preferred_size(&self) -> (usize, usize) {
+        (str_width(&self.label) + 4, 1)
+    }
+    fn is_focusable(&self) -> bool {
+        true
+    }
+    fn set_focused(&mut self, focused: bool) {
+        self.focused = focused;
+    }
+    fn handle_event(&mut self, event: &Event) -> bool {
+        if event.is_activation() || event.is_left_click() {
+            self.toggle();
+            return true;
+        }
+        false
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{KeyCode, KeyModifiers, MouseButton, MouseEventKind};
+    use std::{cell::Cell, rc::Rc};
+
+    #[test]
+    fn test_toggle() {
+        let changes = Rc::new(Cell::new(None));
+        let last = changes.clone();
+        let mut checkbox =
+            Checkbox::new(0, 0, "Wrap").on_change(move |checked| last.set(Some(checked)));
+
+        assert!(checkbox.handle_event(&Event::key(KeyCode::Char(' '), KeyModifiers::NONE)));
+        assert!(checkbox.is_checked());
+        assert_eq!(changes.get(), Some(true));
+
+        assert!(checkbox.handle_event(&Event::mouse(
+            MouseEventKind::Press,
+            MouseButton::Left,
+            0,
+            0
+        )));
+        assert!(!checkbox.is_checked());
+        assert_eq!(changes.get(), Some(false));
+
+        assert!(!checkbox.handle_event(&Event::key(KeyCode::Tab, KeyModifiers::NONE)));
+        checkbox.set_checked(true);
+        assert_eq!(changes.get(), Some(false));
+    }
+
+    #[test]
+    fn test_draw() -> Result<(), Error> {
+        let mut checkbox = Checkbox::new(0, 0, "Wrap").checked(true);
+        let mut buffer = Buffer::new(8, 1);
+        checkbox.draw(&mut buffer)?;
+        assert_eq!(buffer.get_content().iter().collect::<String>(), "[x] Wrap");
+
+        checkbox.set_focused(true);
+        checkbox.toggle();
+        checkbox.draw(&mut buffer)?;
+        assert_eq!(buffer.get_content().iter().collect::<String>(), "[ ] Wrap");
+        let style = buffer.get_cell(1, 0).unwrap().style;
+        assert!(style.attributes.contains(Attributes::REVERSE));
+        assert_eq!(buffer.get_cell(4, 0).unwrap().style, Style::default());
+        Ok(())
+    }
+}
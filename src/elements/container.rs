@@ -1,13 +1,24 @@
-use std::{cell::RefCell, collections::HashMap, rc::Rc};
+use std::{cell::RefCell, rc::Rc};
 
-use crate::{Buffer, Error, UIElement};
+use crate::{tree::check_id, Buffer, ElementList, Error, Flex, FlexItem, Layout, Rect, UIElement};
 
 pub struct Container {
     x: usize,
     y: usize,
     width: usize,
     height: usize,
-    sub_elements: HashMap<String, Rc<RefCell<dyn UIElement>>>,
+    /// The size given at construction, layouts may change `width` and `height`.
+    preferred: (usize, usize),
+    sub_elements: ElementList,
+    arrangement: Arrangement,
+}
+
+/// How a container places its sub elements.
+enum Arrangement {
+    /// Sub elements keep their own coordinates.
+    Absolute,
+    Layout(Layout),
+    Flex(Flex),
 }
 
 impl Container {
@@ -17,47 +28,134 @@ impl Container {
             y,
             width,
             height,
-            sub_elements: HashMap::new(),
+            preferred: (width, height),
+            sub_elements: ElementList::new(),
+            arrangement: Arrangement::Absolute,
+        }
+    }
+
+    /// Positions and sizes the sub elements with the given [`Layout`] instead of
+    /// their own coordinates. The n-th sub element, in drawing order, gets the
+    /// n-th area; sub elements without a matching constraint are left as they are.
+    pub fn layout(mut self, layout: Layout) -> Self {
+        self.set_layout(Some(layout));
+        self
+    }
+
+    pub fn set_layout(&mut self, layout: Option<Layout>) {
+        self.arrangement = match layout {
+            Some(layout) => Arrangement::Layout(layout),
+            None => Arrangement::Absolute,
+        };
+        self.apply_layout();
+    }
+
+    /// Places the sub elements one after the other with a [`Flex`] layout, based on
+    /// their [`UIElement::preferred_size`] and [`UIElement::min_size`], which are
+    /// measured again on every draw. Replaces any [`Layout`].
+    pub fn flex(mut self, flex: Flex) -> Self {
+        self.set_flex(Some(flex));
+        self
+    }
+
+    pub fn set_flex(&mut self, flex: Option<Flex>) {
+        self.arrangement = match flex {
+            Some(flex) => Arrangement::Flex(flex),
+            None => Arrangement::Absolute,
+        };
+        self.apply_layout();
+    }
+
+    fn flex_items(&self) -> Vec<FlexItem> {
+        self.sub_elements
+            .values()
+            .map(|sub| {
+                let sub = sub.borrow();
+                FlexItem {
+                    preferred: sub.preferred_size(),
+                    min: sub.min_size(),
+                }
+            })
+            .collect()
+    }
+
+    fn apply_layout(&self) {
+        let area = Rect::new(0, 0, self.width, self.height);
+        let areas = match &self.arrangement {
+            Arrangement::Absolute => return,
+            Arrangement::Layout(layout) => layout.split(area),
+            Arrangement::Flex(flex) => flex.arrange(area, &self.flex_items()),
+        };
+        for (sub, area) in self.sub_elements.values().zip(areas) {
+            let mut sub = sub.borrow_mut();
+            sub.set_position(area.x, area.y);
+            sub.set_size(area.width, area.height);
         }
     }
+
+    /// The sub elements, e.g. to change their drawing order. Add and remove them
+    /// with [`UIElement::add_sub_element`] and [`UIElement::remove_sub_element`].
+    pub fn sub_elements_mut(&mut self) -> &mut ElementList {
+        &mut self.sub_elements
+    }
 }
 
 impl UIElement for Container {
     fn draw(&self, buffer: &mut Buffer) -> Result<(), Error> {
-        for elem in self.sub_elements.values() {
-            elem.borrow().draw(buffer)?;
-        }
-        Ok(())
+        // The preferred sizes may have changed since the sub elements were added,
+        // e.g. when the content of a text was replaced.
+        self.apply_layout();
+        self.sub_elements.draw(buffer, self.bounds())
     }
     fn set_position(&mut self, x: usize, y: usize) {
-        for sub in self.sub_elements.values_mut() {
-            let (sub_x, sub_y) = sub.borrow().get_position();
-            let diff_x = self.x.abs_diff(sub_x);
-            let diff_y = self.y.abs_diff(sub_y);
-
-            sub.borrow_mut().set_position(x + diff_x, y + diff_y)
-        }
-
         self.x = x;
         self.y = y;
     }
+    fn set_size(&mut self, width: usize, height: usize) {
+        self.width = width;
+        self.height = height;
+        self.apply_layout();
+    }
     fn get_position(&self) -> (usize, usize) {
         (self.x, self.y)
     }
-    fn add_sub_element(&mut self, id: String, element: Rc<RefCell<dyn UIElement>>) {
-        let (x, y) = element.borrow().get_position();
-        element.borrow_mut().set_position(x + self.x, y + self.y);
+    fn get_size(&self) -> (usize, usize) {
+        (self.width, self.height)
+    }
+    fn preferred_size(&self) -> (usize, usize) {
+        match &self.arrangement {
+            Arrangement::Flex(flex) => flex.content_size(&self.flex_items()),
+            _ => self.preferred,
+        }
+    }
+    fn add_sub_element(
+        &mut self,
+        id: String,
+        element: Rc<RefCell<dyn UIElement>>,
+    ) -> Result<(), Error> {
+        check_id(&id)?;
         self.sub_elements.insert(id, element);
+        self.apply_layout();
+        Ok(())
     }
-    fn remove_sub_element(&mut self, id: &str) {
+    fn remove_sub_element(&mut self, id: &str) -> Result<(), Error> {
         self.sub_elements.remove(id);
+        self.apply_layout();
+        Ok(())
+    }
+    fn get_sub_element(&self, id: &str) -> Option<Rc<RefCell<dyn UIElement>>> {
+        self.sub_elements.get(id).cloned()
+    }
+    fn sub_element_ids(&self) -> Vec<String> {
+        self.sub_elements.ids().cloned().collect()
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::elements::Text;
+    use crate::elements::{Rectangle, Text};
+    use crate::{AlignItems, Constraint, JustifyContent, Padding};
 
     #[test]
     fn test_new_container() {
@@ -66,28 +164,218 @@ mod tests {
         assert_eq!(container.sub_elements.len(), 0);
     }
 
+    #[test]
+    fn test_size_and_bounds() {
+        let mut container = Container::new(1, 2, 10, 5);
+        assert_eq!(container.get_size(), (10, 5));
+        container.set_size(4, 3);
+        assert_eq!(container.bounds(), crate::Rect::new(1, 2, 4, 3));
+    }
+
     #[test]
     fn test_add_sub_element() {
         let mut container = Container::new(0, 0, 100, 100);
         let sub_element = Rc::new(RefCell::new(Text::new(10, 10, "Text")));
-        container.add_sub_element("sub1".to_string(), sub_element.clone());
+        container
+            .add_sub_element("sub1".to_string(), sub_element.clone())
+            .unwrap();
         assert_eq!(container.sub_elements.len(), 1);
         assert_eq!(sub_element.borrow().get_position(), (10, 10));
+
+        assert!(matches!(
+            container.add_sub_element("a/b".to_string(), sub_element),
+            Err(Error::InvalidId(_))
+        ));
+        assert_eq!(container.sub_elements.len(), 1);
     }
 
     #[test]
     fn test_set_position() {
         let mut container = Container::new(0, 0, 100, 100);
         let sub_element = Rc::new(RefCell::new(Text::new(10, 10, "Text")));
-        container.add_sub_element("sub1".to_string(), sub_element.clone());
+        container
+            .add_sub_element("sub1".to_string(), sub_element.clone())
+            .unwrap();
         container.set_position(20, 20);
         assert_eq!(container.get_position(), (20, 20));
-        assert_eq!(sub_element.borrow().get_position(), (30, 30));
+        // Sub elements are positioned relative to their parent.
+        assert_eq!(sub_element.borrow().get_position(), (10, 10));
+    }
+
+    #[test]
+    fn test_nested_draw_is_translated() -> Result<(), Error> {
+        let mut outer = Container::new(1, 1, 8, 3);
+        let inner = Rc::new(RefCell::new(Container::new(2, 1, 4, 2)));
+        let text = Rc::new(RefCell::new(Text::new(1, 0, "ab")));
+        inner
+            .borrow_mut()
+            .add_sub_element("text".to_string(), text)?;
+        outer.add_sub_element("inner".to_string(), inner.clone())?;
+
+        let mut buffer = Buffer::new(10, 4);
+        outer.draw(&mut buffer)?;
+        assert_eq!(buffer.get_cell(4, 2).unwrap().symbol, "a");
+        assert_eq!(buffer.get_cell(5, 2).unwrap().symbol, "b");
+
+        outer.set_position(0, 0);
+        let mut buffer = Buffer::new(10, 4);
+        outer.draw(&mut buffer)?;
+        assert_eq!(buffer.get_cell(3, 1).unwrap().symbol, "a");
+        Ok(())
+    }
+
+    #[test]
+    fn test_reparenting_keeps_coordinates() {
+        let mut first = Container::new(5, 5, 10, 10);
+        let mut second = Container::new(20, 0, 10, 10);
+        let text = Rc::new(RefCell::new(Text::new(2, 3, "Text")));
+
+        first
+            .add_sub_element("text".to_string(), text.clone())
+            .unwrap();
+        first.remove_sub_element("text").unwrap();
+        second
+            .add_sub_element("text".to_string(), text.clone())
+            .unwrap();
+        second
+            .add_sub_element("text".to_string(), text.clone())
+            .unwrap();
+        assert_eq!(text.borrow().get_position(), (2, 3));
+    }
+
+    #[test]
+    fn test_sub_elements_are_clipped() -> Result<(), Error> {
+        let mut container = Container::new(1, 0, 3, 1);
+        let sub_element = Rc::new(RefCell::new(Text::new(0, 0, "Hello")));
+        container.add_sub_element("sub1".to_string(), sub_element)?;
+
+        let mut buffer = Buffer::new(6, 1);
+        container.draw(&mut buffer)?;
+        assert_eq!(buffer.get_content(), vec![' ', 'H', 'e', 'l', ' ', ' ']);
+        Ok(())
+    }
+
+    #[test]
+    fn test_sub_element_order() -> Result<(), Error> {
+        let mut container = Container::new(0, 0, 3, 1);
+        for id in ["a", "b", "c"] {
+            let text = Rc::new(RefCell::new(Text::new(0, 0, id)));
+            container.add_sub_element(id.to_string(), text)?;
+        }
+
+        let mut buffer = Buffer::new(3, 1);
+        container.draw(&mut buffer)?;
+        assert_eq!(buffer.get_content()[0], 'c');
+
+        assert!(container.sub_elements_mut().bring_to_front("a"));
+        container.draw(&mut buffer)?;
+        assert_eq!(buffer.get_content()[0], 'a');
+
+        assert!(container.sub_elements_mut().send_to_back("a"));
+        assert!(container.sub_elements_mut().lower("c"));
+        container.draw(&mut buffer)?;
+        assert_eq!(buffer.get_content()[0], 'b');
+        Ok(())
+    }
+
+    #[test]
+    fn test_layout() {
+        let mut container = Container::new(2, 1, 10, 4).layout(Layout::horizontal(vec![
+            Constraint::Length(3),
+            Constraint::Fill(1),
+        ]));
+        let left = Rc::new(RefCell::new(Rectangle::new(0, 0, 1, 1)));
+        let right = Rc::new(RefCell::new(Rectangle::new(0, 0, 1, 1).bottom_right('+')));
+        container
+            .add_sub_element("left".to_string(), left.clone())
+            .unwrap();
+        container
+            .add_sub_element("right".to_string(), right.clone())
+            .unwrap();
+
+        assert_eq!(left.borrow().get_position(), (0, 0));
+        assert_eq!(right.borrow().get_position(), (3, 0));
+
+        container.set_size(20, 4);
+        container.set_position(0, 0);
+        assert_eq!(right.borrow().get_position(), (3, 0));
+
+        let mut buffer = Buffer::new(20, 4);
+        container.draw(&mut buffer).unwrap();
+        // The right rectangle was resized to fill the rest of the container.
+        assert_eq!(buffer.get_cell(19, 3).unwrap().symbol, "+");
+    }
+
+    #[test]
+    fn test_flex_measures_again_before_drawing() -> Result<(), Error> {
+        let mut container = Container::new(0, 0, 12, 1).flex(Flex::row().gap(1));
+        let name = Rc::new(RefCell::new(Text::new(0, 0, "ab")));
+        let value = Rc::new(RefCell::new(Text::new(0, 0, "cd")));
+        container.add_sub_element("name".to_string(), name.clone())?;
+        container.add_sub_element("value".to_string(), value.clone())?;
+        assert_eq!(value.borrow().get_position(), (3, 0));
+
+        name.borrow_mut().set_content("abcd");
+        let mut buffer = Buffer::new(12, 1);
+        container.draw(&mut buffer)?;
+        assert_eq!(name.borrow().get_size(), (4, 1));
+        assert_eq!(value.borrow().get_position(), (5, 0));
+        assert_eq!(
+            buffer.get_content().iter().collect::<String>(),
+            "abcd cd     "
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_flex() {
+        let toolbar = Flex::row()
+            .gap(1)
+            .padding(Padding::symmetric(0, 1))
+            .justify_content(JustifyContent::End)
+            .align_items(AlignItems::Stretch);
+        let mut container = Container::new(0, 0, 20, 3).flex(toolbar);
+        let open = Rc::new(RefCell::new(Text::new(0, 0, "Open")));
+        let button = Rc::new(RefCell::new(Rectangle::new(0, 0, 6, 1)));
+        container
+            .add_sub_element("open".to_string(), open.clone())
+            .unwrap();
+        container
+            .add_sub_element("button".to_string(), button.clone())
+            .unwrap();
+
+        assert_eq!(open.borrow().get_position(), (8, 0));
+        assert_eq!(button.borrow().get_position(), (13, 0));
+        assert_eq!(button.borrow().preferred_size(), (6, 1));
+        assert_eq!(container.preferred_size(), (13, 1));
+
+        container.set_position(0, 5);
+        assert_eq!(open.borrow().get_position(), (8, 0));
+    }
+
+    #[test]
+    fn test_nested_flex_measures_children() {
+        let row = Container::new(0, 0, 0, 0).flex(Flex::row().gap(1));
+        let row = Rc::new(RefCell::new(row));
+        for id in ["ab", "cde"] {
+            let text = Rc::new(RefCell::new(Text::new(0, 0, id)));
+            row.borrow_mut()
+                .add_sub_element(id.to_string(), text)
+                .unwrap();
+        }
+        assert_eq!(row.borrow().preferred_size(), (6, 1));
+
+        let mut column = Container::new(0, 0, 10, 10).flex(Flex::column());
+        let label = Rc::new(RefCell::new(Text::new(0, 0, "Name")));
+        column.add_sub_element("label".to_string(), label).unwrap();
+        column
+            .add_sub_element("row".to_string(), row.clone())
+            .unwrap();
+        assert_eq!(row.borrow().get_position(), (0, 1));
     }
 
     #[test]
-    #[should_panic]
-    fn test_set_position_panic() {
+    fn test_set_position_leaves_sub_elements_alone() {
         struct FaultyElement;
         impl UIElement for FaultyElement {
             fn draw(&self, _buffer: &mut Buffer) -> Result<(), Error> {
@@ -99,13 +387,13 @@ mod tests {
             fn get_position(&self) -> (usize, usize) {
                 (0, 0)
             }
-            fn add_sub_element(&mut self, _id: String, _element: Rc<RefCell<dyn UIElement>>) {}
-            fn remove_sub_element(&mut self, _id: &str) {}
         }
 
         let mut container = Container::new(0, 0, 100, 100);
         let faulty_element = Rc::new(RefCell::new(FaultyElement));
-        container.add_sub_element("faulty".to_string(), faulty_element);
-        container.set_position(10, 10); // This should panic
+        container
+            .add_sub_element("faulty".to_string(), faulty_element)
+            .unwrap();
+        container.set_position(10, 10); // Doesn't reach the sub element
     }
 }
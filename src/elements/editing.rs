@@ -0,0 +1,106 @@
+//! Cursor movement over grapheme clusters and words, shared by the text editing elements.
+//! Positions are byte offsets that always fall on grapheme boundaries.
+
+use unicode_segmentation::UnicodeSegmentation;
+
+use crate::buffer::str_width;
+
+/// Called with the value of a text editing element.
+pub(crate) type TextCallback = Box<dyn FnMut(&str)>;
+
+fn is_space(grapheme: &str) -> bool {
+    grapheme.chars().all(char::is_whitespace)
+}
+
+/// The start of the grapheme before `pos`.
+pub(crate) fn prev_grapheme(content: &str, pos: usize) -> usize {
+    content[..pos]
+        .grapheme_indices(true)
+        .next_back()
+        .map_or(0, |(i, _)| i)
+}
+
+/// The end of the grapheme after `pos`.
+pub(crate) fn next_grapheme(content: &str, pos: usize) -> usize {
+    content[pos..]
+        .graphemes(true)
+        .next()
+        .map_or(pos, |grapheme| pos + grapheme.len())
+}
+
+/// The start of the word before `pos`, skipping whitespace in between.
+pub(crate) fn prev_word(content: &str, pos: usize) -> usize {
+    let mut start = pos;
+    let mut in_word = false;
+    for (i, grapheme) in content[..pos].grapheme_indices(true).rev() {
+        let space = is_space(grapheme);
+        if space && in_word {
+            break;
+        }
+        in_word |= !space;
+        start = i;
+    }
+    start
+}
+
+/// The end of the word after `pos`, skipping whitespace in between.
+pub(crate) fn next_word(content: &str, pos: usize) -> usize {
+    let mut end = pos;
+    let mut in_word = false;
+    for (i, grapheme) in content[pos..].grapheme_indices(true) {
+        let space = is_space(grapheme);
+        if space && in_word {
+            break;
+        }
+        in_word |= !space;
+        end = pos + i + grapheme.len();
+    }
+    end
+}
+
+/// The number of grapheme clusters in `content`.
+pub(crate) fn grapheme_count(content: &str) -> usize {
+    content.graphemes(true).count()
+}
+
+/// The position of the grapheme at `column`, or of the end of `content` if it is narrower.
+pub(crate) fn position_at_column(content: &str, column: usize) -> usize {
+    let mut width = 0;
+    for (i, grapheme) in content.grapheme_indices(true) {
+        let next = width + str_width(grapheme);
+        if next > column {
+            return i;
+        }
+        width = next;
+    }
+    content.len()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_graphemes() {
+        let content = "ae\u{301}日";
+        assert_eq!(next_grapheme(content, 0), 1);
+        assert_eq!(next_grapheme(content, 1), 4);
+        assert_eq!(prev_grapheme(content, 4), 1);
+        assert_eq!(prev_grapheme(content, 0), 0);
+        assert_eq!(grapheme_count(content), 3);
+        assert_eq!(position_at_column(content, 2), 4);
+        assert_eq!(position_at_column(content, 3), 4);
+        assert_eq!(position_at_column(content, 9), content.len());
+    }
+
+    #[test]
+    fn test_words() {
+        let content = "one  two three";
+        assert_eq!(next_word(content, 0), 3);
+        assert_eq!(next_word(content, 3), 8);
+        assert_eq!(next_word(content, 14), 14);
+        assert_eq!(prev_word(content, 14), 9);
+        assert_eq!(prev_word(content, 9), 5);
+        assert_eq!(prev_word(content, 4), 0);
+    }
+}
@@ -0,0 +1,445 @@
+use std::{cell::RefCell, collections::HashMap, rc::Rc};
+
+use crate::{layout::solve, tree::check_id, Buffer, Constraint, ElementList, Error, UIElement};
+
+/// The size of a row or a column of a [`Grid`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Track {
+    /// Exactly the given number of cells.
+    Fixed(usize),
+    /// Shares the space left by the other tracks proportionally to the given weight.
+    Fraction(u16),
+    /// As large as the largest preferred size of the sub elements spanning only this track.
+    Auto,
+}
+
+/// The cells a sub element of a [`Grid`] covers, rows and columns starting at 0.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct GridArea {
+    pub row: usize,
+    pub column: usize,
+    pub row_span: usize,
+    pub column_span: usize,
+}
+
+impl GridArea {
+    pub fn new(row: usize, column: usize) -> Self {
+        Self {
+            row,
+            column,
+            row_span: 1,
+            column_span: 1,
+        }
+    }
+
+    pub fn span(mut self, rows: usize, columns: usize) -> Self {
+        self.row_span = rows.max(1);
+        self.column_span = columns.max(1);
+        self
+    }
+}
+
+/// Places its sub elements into the cells of a grid of rows and columns.
+///
+/// Sub elements added with [`Grid::add_sub_element_at`] or given an area with
+/// [`Grid::place`] cover that area, the others take the next free cell, row by
+/// row, in drawing order. Sub elements that don't fit into the grid are left as they are.
+pub struct Grid {
+    x: usize,
+    y: usize,
+    width: usize,
+    height: usize,
+    /// The size given at construction, layouts may change `width` and `height`.
+    preferred: (usize, usize),
+    rows: Vec<Track>,
+    columns: Vec<Track>,
+    row_gap: usize,
+    column_gap: usize,
+    sub_elements: ElementList,
+    areas: HashMap<String, GridArea>,
+}
+
+impl Grid {
+    pub fn new(x: usize, y: usize, width: usize, height: usize) -> Self {
+        Self {
+            x,
+            y,
+            width,
+            height,
+            preferred: (width, height),
+            rows: Vec::new(),
+            columns: Vec::new(),
+            row_gap: 0,
+            column_gap: 0,
+            sub_elements: ElementList::new(),
+            areas: HashMap::new(),
+        }
+    }
+
+    pub fn rows(mut self, rows: Vec<Track>) -> Self {
+        self.rows = rows;
+        self.apply_layout();
+        self
+    }
+    pub fn columns(mut self, columns: Vec<Track>) -> Self {
+        self.columns = columns;
+        self.apply_layout();
+        self
+    }
+    /// Space between rows and between columns.
+    pub fn gap(mut self, gap: usize) -> Self {
+        self.row_gap = gap;
+        self.column_gap = gap;
+        self.apply_layout();
+        self
+    }
+    pub fn row_gap(mut self, gap: usize) -> Self {
+        self.row_gap = gap;
+        self.apply_layout();
+        self
+    }
+    pub fn column_gap(mut self, gap: usize) -> Self {
+        self.column_gap = gap;
+        self.apply_layout();
+        self
+    }
+
+    /// Adds a sub element covering the given area.
+    pub fn add_sub_element_at(
+        &mut self,
+        id: String,
+        element: Rc<RefCell<dyn UIElement>>,
+        area: GridArea,
+    ) -> Result<(), Error> {
+        check_id(&id)?;
+        self.areas.insert(id.clone(), area);
+        self.add_sub_element(id, element)
+    }
+
+    /// Moves a sub element, present or added later, to the given area.
+    pub fn place(&mut self, id: &str, area: GridArea) {
+        self.areas.insert(id.to_string(), area);
+        self.apply_layout();
+    }
+
+    /// The area each sub element covers, in drawing order. Explicit areas are
+    /// reserved first, the other sub elements fill the free cells.
+    fn resolve_areas(&self) -> Vec<Option<GridArea>> {
+        let (rows, columns) = (self.rows.len(), self.columns.len());
+        let mut taken = vec![false; rows * columns];
+        let mark = |area: &GridArea, taken: &mut Vec<bool>| {
+            for row in area.row..(area.row + area.row_span).min(rows) {
+                for column in area.column..(area.column + area.column_span).min(columns) {
+                    taken[row * columns + column] = true;
+                }
+            }
+        };
+
+        let mut areas: Vec<Option<GridArea>> = self
+            .sub_elements
+            .ids()
+            .map(|id| {
+                let area = self
+                    .areas
+                    .get(id)
+                    .filter(|area| area.row < rows && area.column < columns)?;
+                mark(area, &mut taken);
+                Some(*area)
+            })
+            .collect();
+
+        let mut next = 0;
+        for (id, area) in self.sub_elements.ids().zip(areas.iter_mut()) {
+            if self.areas.contains_key(id) {
+                continue;
+            }
+            while next < taken.len() && taken[next] {
+                next += 1;
+            }
+            if next == taken.len() {
+                break;
+            }
+            taken[next] = true;
+            *area = Some(GridArea::new(next / columns, next % columns));
+        }
+
+        areas
+    }
+
+    /// The largest preferred size of the sub elements spanning a single track,
+    /// for each row and each column.
+    fn measure(&self, areas: &[Option<GridArea>]) -> (Vec<usize>, Vec<usize>) {
+        let mut heights = vec![0; self.rows.len()];
+        let mut widths = vec![0; self.columns.len()];
+        for (sub, area) in self.sub_elements.values().zip(areas) {
+            let Some(area) = area else {
+                continue;
+            };
+            let (width, height) = sub.borrow().preferred_size();
+            if area.row_span == 1 {
+                heights[area.row] = heights[area.row].max(height);
+            }
+            if area.column_span == 1 {
+                widths[area.column] = widths[area.column].max(width);
+            }
+        }
+        (heights, widths)
+    }
+
+    fn apply_layout(&self) {
+        let areas = self.resolve_areas();
+        let (heights, widths) = self.measure(&areas);
+        let rows = track_offsets(&self.rows, &heights, self.height, self.row_gap);
+        let columns = track_offsets(&self.columns, &widths, self.width, self.column_gap);
+
+        for (sub, area) in self.sub_elements.values().zip(areas) {
+            let Some(area) = area else {
+                continue;
+            };
+            let rect = span_rect(&columns, area.column, area.column_span, self.column_gap)
+                .zip(span_rect(&rows, area.row, area.row_span, self.row_gap));
+            let Some(((x, width), (y, height))) = rect else {
+                continue;
+            };
+
+            let mut sub = sub.borrow_mut();
+            sub.set_position(x, y);
+            sub.set_size(width, height);
+        }
+    }
+
+    /// The sub elements, like [`crate::Container::sub_elements_mut`].
+    pub fn sub_elements_mut(&mut self) -> &mut ElementList {
+        &mut self.sub_elements
+    }
+}
+
+/// Returns the (offset, size) of each track.
+fn track_offsets(
+    tracks: &[Track],
+    measured: &[usize],
+    total: usize,
+    gap: usize,
+) -> Vec<(usize, usize)> {
+    let constraints: Vec<Constraint> = tracks
+        .iter()
+        .zip(measured)
+        .map(|(track, measured)| match *track {
+            Track::Fixed(size) => Constraint::Length(size),
+            Track::Auto => Constraint::Length(*measured),
+            Track::Fraction(weight) => Constraint::Fill(weight),
+        })
+        .collect();
+    let gaps = gap * tracks.len().saturating_sub(1);
+
+    let mut offset = 0;
+    solve(&constraints, total.saturating_sub(gaps))
+        .into_iter()
+        .map(|size| {
+            let track = (offset, size);
+            offset += size + gap;
+            track
+        })
+        .collect()
+}
+
+/// The (offset, size) covered by `span` tracks starting at `start`, gaps included.
+fn span_rect(
+    tracks: &[(usize, usize)],
+    start: usize,
+    span: usize,
+    gap: usize,
+) -> Option<(usize, usize)> {
+    let covered = tracks.get(start..(start + span).min(tracks.len()))?;
+    let (offset, _) = covered.first()?;
+    let size = covered.iter().map(|(_, size)| size).sum::<usize>() + gap * (covered.len() - 1);
+    Some((*offset, size))
+}
+
+impl UIElement for Grid {
+    fn draw(&self, buffer: &mut Buffer) -> Result<(), Error> {
+        self.sub_elements.draw(buffer, self.bounds())
+    }
+    fn set_position(&mut self, x: usize, y: usize) {
+        self.x = x;
+        self.y = y;
+    }
+    fn set_size(&mut self, width: usize, height: usize) {
+        self.width = width;
+        self.height = height;
+        self.apply_layout();
+    }
+    fn get_position(&self) -> (usize, usize) {
+        (self.x, self.y)
+    }
+    fn get_size(&self) -> (usize, usize) {
+        (self.width, self.height)
+    }
+    fn preferred_size(&self) -> (usize, usize) {
+        if self.rows.is_empty() || self.columns.is_empty() {
+            return self.preferred;
+        }
+        // Fractions are measured like auto tracks.
+        let (heights, widths) = self.measure(&self.resolve_areas());
+        let size = |tracks: &[Track], measured: &[usize], gap: usize| {
+            tracks
+                .iter()
+                .zip(measured)
+                .map(|(track, measured)| match *track {
+                    Track::Fixed(size) => size,
+                    Track::Auto | Track::Fraction(_) => *measured,
+                })
+                .sum::<usize>()
+                + gap * tracks.len().saturating_sub(1)
+        };
+        (
+            size(&self.columns, &widths, self.column_gap),
+            size(&self.rows, &heights, self.row_gap),
+        )
+    }
+    fn add_sub_element(
+        &mut self,
+        id: String,
+        element: Rc<RefCell<dyn UIElement>>,
+    ) -> Result<(), Error> {
+        check_id(&id)?;
+        self.sub_elements.insert(id, element);
+        self.apply_layout();
+        Ok(())
+    }
+    fn remove_sub_element(&mut self, id: &str) -> Result<(), Error> {
+        self.sub_elements.remove(id);
+        self.areas.remove(id);
+        self.apply_layout();
+        Ok(())
+    }
+    fn get_sub_element(&self, id: &str) -> Option<Rc<RefCell<dyn UIElement>>> {
+        self.sub_elements.get(id).cloned()
+    }
+    fn sub_element_ids(&self) -> Vec<String> {
+        self.sub_elements.ids().cloned().collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::elements::{Rectangle, Text};
+
+    fn rectangle() -> Rc<RefCell<Rectangle>> {
+        Rc::new(RefCell::new(Rectangle::new(0, 0, 1, 1)))
+    }
+
+    #[test]
+    fn test_auto_placement() {
+        let mut grid = Grid::new(1, 1, 10, 4)
+            .columns(vec![Track::Fraction(1), Track::Fraction(1)])
+            .rows(vec![Track::Fixed(2), Track::Fixed(2)]);
+        let cells: Vec<_> = (0..4).map(|_| rectangle()).collect();
+        for (i, cell) in cells.iter().enumerate() {
+            grid.add_sub_element(i.to_string(), cell.clone()).unwrap();
+        }
+
+        let positions: Vec<_> = cells.iter().map(|c| c.borrow().get_position()).collect();
+        assert_eq!(positions, vec![(0, 0), (5, 0), (0, 2), (5, 2)]);
+    }
+
+    #[test]
+    fn test_rejected_id_keeps_no_area() {
+        let mut grid = Grid::new(0, 0, 10, 2).columns(vec![Track::Fraction(1); 2]);
+        assert!(matches!(
+            grid.add_sub_element_at("a/b".to_string(), rectangle(), GridArea::new(0, 1)),
+            Err(Error::InvalidId(_))
+        ));
+        assert!(grid.areas.is_empty());
+    }
+
+    #[test]
+    fn test_spans_and_gaps() -> Result<(), Error> {
+        let mut grid = Grid::new(0, 0, 11, 5)
+            .columns(vec![
+                Track::Fixed(3),
+                Track::Fraction(1),
+                Track::Fraction(1),
+            ])
+            .rows(vec![Track::Fixed(1), Track::Fraction(1)])
+            .gap(1);
+        let header = rectangle();
+        let sidebar = rectangle();
+        let body = Rc::new(RefCell::new(Rectangle::new(0, 0, 1, 1).bottom_right('+')));
+        grid.add_sub_element_at(
+            "header".to_string(),
+            header.clone(),
+            GridArea::new(0, 0).span(1, 3),
+        )?;
+        grid.add_sub_element("sidebar".to_string(), sidebar.clone())?;
+        grid.add_sub_element_at(
+            "body".to_string(),
+            body.clone(),
+            GridArea::new(1, 1).span(1, 2),
+        )?;
+
+        assert_eq!(header.borrow().get_position(), (0, 0));
+        // The first row is taken by the header.
+        assert_eq!(sidebar.borrow().get_position(), (0, 2));
+        assert_eq!(body.borrow().get_position(), (4, 2));
+
+        let mut buffer = Buffer::new(11, 5);
+        grid.draw(&mut buffer)?;
+        assert_eq!(buffer.get_cell(10, 4).unwrap().symbol, "+");
+        Ok(())
+    }
+
+    #[test]
+    fn test_auto_tracks() {
+        let mut grid = Grid::new(0, 0, 20, 3)
+            .columns(vec![Track::Auto, Track::Fraction(1)])
+            .rows(vec![Track::Auto, Track::Auto])
+            .column_gap(1);
+        let label = Rc::new(RefCell::new(Text::new(0, 0, "Name:")));
+        let input = rectangle();
+        let hint = Rc::new(RefCell::new(Text::new(0, 0, "Age:")));
+        grid.add_sub_element("label".to_string(), label.clone())
+            .unwrap();
+        grid.add_sub_element("input".to_string(), input.clone())
+            .unwrap();
+        grid.add_sub_element("hint".to_string(), hint.clone())
+            .unwrap();
+
+        assert_eq!(input.borrow().get_position(), (6, 0));
+        assert_eq!(hint.borrow().get_position(), (0, 1));
+        assert_eq!(grid.preferred_size(), (7, 2));
+    }
+
+    #[test]
+    fn test_place_and_remove() {
+        let mut grid = Grid::new(0, 0, 4, 4)
+            .columns(vec![Track::Fixed(2), Track::Fixed(2)])
+            .rows(vec![Track::Fixed(2), Track::Fixed(2)]);
+        let a = rectangle();
+        let b = rectangle();
+        grid.add_sub_element("a".to_string(), a.clone()).unwrap();
+        grid.add_sub_element("b".to_string(), b.clone()).unwrap();
+        assert_eq!(b.borrow().get_position(), (2, 0));
+
+        grid.place("a", GridArea::new(1, 1));
+        assert_eq!(a.borrow().get_position(), (2, 2));
+        assert_eq!(b.borrow().get_position(), (0, 0));
+
+        grid.remove_sub_element("a").unwrap();
+        grid.set_position(5, 5);
+        assert_eq!(b.borrow().get_position(), (0, 0));
+    }
+
+    #[test]
+    fn test_elements_outside_the_grid_are_left_alone() {
+        let mut grid = Grid::new(0, 0, 4, 1)
+            .columns(vec![Track::Fixed(2)])
+            .rows(vec![Track::Fixed(1)]);
+        let a = rectangle();
+        let b = Rc::new(RefCell::new(Rectangle::new(3, 0, 1, 1)));
+        grid.add_sub_element("a".to_string(), a).unwrap();
+        grid.add_sub_element("b".to_string(), b.clone()).unwrap();
+        assert_eq!(b.borrow().get_position(), (3, 0));
+    }
+}
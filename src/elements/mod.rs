@@ -1,7 +1,24 @@
+pub mod button;
+pub mod checkbox;
 pub mod container;
+mod editing;
+pub mod grid;
+pub mod radio_group;
 pub mod rectangle;
+pub mod scroll_view;
 pub mod text;
+pub mod text_area;
+pub mod text_input;
+pub mod toggle;
 
+pub use button::Button;
+pub use checkbox::Checkbox;
 pub use container::Container;
+pub use grid::{Grid, GridArea, Track};
+pub use radio_group::RadioGroup;
 pub use rectangle::Rectangle;
-pub use text::Text;
+pub use scroll_view::ScrollView;
+pub use text::{Alignment, Text, VerticalAlignment, Wrap};
+pub use text_area::TextArea;
+pub use text_input::TextInput;
+pub use toggle::Toggle;
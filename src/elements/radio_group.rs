@@ -0,0 +1,235 @@
+use crate::{buffer::str_width, Attributes, Buffer, Error, Event, KeyCode, Style, UIElement};
+
+/// A list of options of which at most one is selected, drawn one per row as
+/// `(•) option` or `( ) option`.
+///
+/// Up and Down move the highlighted option, Enter and Space select it. A click
+/// selects the option on the clicked row, mouse coordinates are expected relative
+/// to the group.
+pub struct RadioGroup {
+    x: usize,
+    y: usize,
+    options: Vec<String>,
+    selected: Option<usize>,
+    highlighted: usize,
+    style: Style,
+    focus_style: Style,
+    focused: bool,
+    on_change: Option<Box<dyn FnMut(usize)>>,
+}
+
+impl RadioGroup {
+    pub fn new(x: usize, y: usize, options: &[&str]) -> Self {
+        Self {
+            x,
+            y,
+            options: options.iter().map(|option| option.to_string()).collect(),
+            selected: None,
+            highlighted: 0,
+            style: Style::default(),
+            focus_style: Style::new().attributes(Attributes::REVERSE),
+            focused: false,
+            on_change: None,
+        }
+    }
+
+    pub fn selected(mut self, index: usize) -> Self {
+        self.set_selected(Some(index));
+        self
+    }
+    pub fn style(mut self, style: Style) -> Self {
+        self.style = style;
+        self
+    }
+    /// The style of the highlighted option while the group has the focus.
+    pub fn focus_style(mut self, style: Style) -> Self {
+        self.focus_style = style;
+        self
+    }
+    /// Called with the index of the option selected by an event.
+    pub fn on_change(mut self, callback: impl FnMut(usize) + 'static) -> Self {
+        self.on_change = Some(Box::new(callback));
+        self
+    }
+
+    pub fn options(&self) -> &[String] {
+        &self.options
+    }
+    /// The index of the selected option.
+    pub fn get_selected(&self) -> Option<usize> {
+        self.selected
+    }
+    /// Selects an option without calling `on_change`, indexes out of range are ignored.
+    pub fn set_selected(&mut self, index: Option<usize>) {
+        if index.is_some_and(|index| index >= self.options.len()) {
+            return;
+        }
+        self.selected = index;
+        if let Some(index) = index {
+            self.highlighted = index;
+        }
+    }
+    /// The index of the option Enter and Space select.
+    pub fn highlighted(&self) -> usize {
+        self.highlighted
+    }
+
+    pub fn is_focused(&self) -> bool {
+        self.focused
+    }
+
+    /// Selects an option, calling `on_change` if the selection changed.
+    pub fn select(&mut self, index: usize) {
+        if index >= self.options.len() {
+            return;
+        }
+        self.highlighted = index;
+        if self.selected == Some(index) {
+            return;
+        }
+        self.selected = Some(index);
+        if let Some(on_change) = self.on_change.as_mut() {
+            on_change(index);
+        }
+    }
+}
+
+impl UIElement for RadioGroup {
+    fn draw(&self, buffer: &mut Buffer) -> Result<(), Error> {
+        for (i, option) in self.options.iter().enumerate() {
+            let mark = if self.selected == Some(i) {
+                "(•)"
+            } else {
+                "( )"
+            };
+            let style = if self.focused && i == self.highlighted {
+                self.style.patch(self.focus_style)
+            } else {
+                self.style
+            };
+            buffer.draw_str(self.x, self.y + i, mark, style)?;
+            buffer.draw_str(self.x + 4, self.y + i, option, self.style)?;
+        }
+        Ok(())
+    }
+    fn set_position(&mut self, x: usize, y: usize) {
+        self.x = x;
+        self.y = y;
+    }
+    fn get_position(&self) -> (usize, usize) {
+        (self.x, self.y)
+    }
+    fn preferred_size(&self) -> (usize, usize) {
+        let width = self.options.iter().map(|option| str_width(option)).max();
+        (width.map_or(0, |width| width + 4), self.options.len())
+    }
+    fn is_focusable(&self) -> bool {
+        true
+    }
+    fn set_focused(&mut self, focused: bool) {
+        self.focused = focused;
+    }
+    fn handle_event(&mut self, event: &Event) -> bool {
+        if event.is_activation() {
+            self.select(self.highlighted);
+            return true;
+        }
+        if event.is_left_click() {
+            if let Event::Mouse { y, .. } = *event {
+                if let Ok(y) = usize::try_from(y) {
+                    self.select(y);
+                }
+            }
+            return true;
+        }
+        match event {
+            Event::Key {
+                code: KeyCode::Up, ..
+            } => self.highlighted = self.highlighted.saturating_sub(1),
+            Event::Key {
+                code: KeyCode::Down,
+                ..
+            } => {
+                self.highlighted = (self.highlighted + 1).min(self.options.len().saturating_sub(1))
+            }
+            _ => return false,
+        }
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{KeyModifiers, MouseButton, MouseEventKind};
+    use std::{cell::Cell, rc::Rc};
+
+    fn key(code: KeyCode) -> Event {
+        Event::key(code, KeyModifiers::NONE)
+    }
+
+    #[test]
+    fn test_keys() {
+        let selected = Rc::new(Cell::new(None));
+        let last = selected.clone();
+        let mut group = RadioGroup::new(0, 0, &["Small", "Medium", "Large"])
+            .on_change(move |index| last.set(Some(index)));
+
+        assert!(group.handle_event(&key(KeyCode::Down)));
+        assert!(group.handle_event(&key(KeyCode::Down)));
+        assert!(group.handle_event(&key(KeyCode::Down)));
+        assert_eq!(group.highlighted(), 2);
+        assert_eq!(group.get_selected(), None);
+
+        assert!(group.handle_event(&key(KeyCode::Up)));
+        assert!(group.handle_event(&key(KeyCode::Enter)));
+        assert_eq!(group.get_selected(), Some(1));
+        assert_eq!(selected.get(), Some(1));
+
+        assert!(!group.handle_event(&key(KeyCode::Char('x'))));
+    }
+
+    #[test]
+    fn test_click_selects_row() {
+        let mut group = RadioGroup::new(5, 5, &["a", "b", "c"]).selected(0);
+        assert!(group.handle_event(&Event::mouse(
+            MouseEventKind::Press,
+            MouseButton::Left,
+            1,
+            2
+        )));
+        assert_eq!(group.get_selected(), Some(2));
+        assert_eq!(group.highlighted(), 2);
+
+        // Clicking below the options changes nothing.
+        assert!(group.handle_event(&Event::mouse(
+            MouseEventKind::Press,
+            MouseButton::Left,
+            1,
+            7
+        )));
+        assert_eq!(group.get_selected(), Some(2));
+    }
+
+    #[test]
+    fn test_draw() -> Result<(), Error> {
+        let mut group = RadioGroup::new(0, 0, &["On", "Off"]).selected(1);
+        group.set_focused(true);
+        assert_eq!(group.get_size(), (7, 2));
+
+        let mut buffer = Buffer::new(7, 2);
+        group.draw(&mut buffer)?;
+        assert_eq!(
+            buffer.get_content().iter().collect::<String>(),
+            "( ) On (•) Off"
+        );
+        assert_eq!(buffer.get_cell(1, 0).unwrap().style, Style::default());
+        assert!(buffer
+            .get_cell(1, 1)
+            .unwrap()
+            .style
+            .attributes
+            .contains(Attributes::REVERSE));
+        Ok(())
+    }
+}
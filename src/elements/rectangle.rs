@@ -1,19 +1,22 @@
-use std::{cell::RefCell, collections::HashMap, rc::Rc};
+use std::{cell::RefCell, rc::Rc};
 
-use crate::{Buffer, Error, UIElement};
+use crate::{tree::check_id, Buffer, ElementList, Error, Style, UIElement};
 
 pub struct Rectangle {
     x: usize,
     y: usize,
     width: usize,
     height: usize,
+    /// The size given at construction, layouts may change `width` and `height`.
+    preferred: (usize, usize),
     horizontal_border: char,
     vertical_border: char,
     top_left: char,
     top_right: char,
     bottom_left: char,
     bottom_right: char,
-    sub_elements: HashMap<String, Rc<RefCell<dyn UIElement>>>,
+    style: Style,
+    sub_elements: ElementList,
 }
 
 impl Rectangle {
@@ -23,13 +26,15 @@ impl Rectangle {
             y,
             width,
             height,
+            preferred: (width, height),
             top_left: ' ',
             top_right: ' ',
             bottom_left: ' ',
             bottom_right: ' ',
             vertical_border: ' ',
             horizontal_border: ' ',
-            sub_elements: HashMap::new(),
+            style: Style::default(),
+            sub_elements: ElementList::new(),
         }
     }
 
@@ -57,55 +62,81 @@ impl Rectangle {
         self.vertical_border = character;
         self
     }
+    pub fn style(mut self, style: Style) -> Self {
+        self.style = style;
+        self
+    }
+
+    /// The sub elements, like [`crate::Container::sub_elements_mut`].
+    pub fn sub_elements_mut(&mut self) -> &mut ElementList {
+        &mut self.sub_elements
+    }
 }
 
 impl UIElement for Rectangle {
     fn draw(&self, buffer: &mut Buffer) -> Result<(), Error> {
-        buffer.draw_char(self.x, self.y, self.top_left)?;
-        buffer.draw_char(self.x + self.width - 1, self.y, self.top_right)?;
-        buffer.draw_char(self.x, self.y + self.height - 1, self.bottom_left)?;
-        buffer.draw_char(
-            self.x + self.width - 1,
-            self.y + self.height - 1,
-            self.bottom_right,
-        )?;
+        if self.width == 0 || self.height == 0 {
+            return Ok(());
+        }
+
+        let right = self.x + self.width - 1;
+        let bottom = self.y + self.height - 1;
+
+        buffer.draw_char_styled(self.x, self.y, self.top_left, self.style)?;
+        buffer.draw_char_styled(right, self.y, self.top_right, self.style)?;
+        buffer.draw_char_styled(self.x, bottom, self.bottom_left, self.style)?;
+        buffer.draw_char_styled(right, bottom, self.bottom_right, self.style)?;
 
         for i in 1..self.width - 1 {
-            buffer.draw_char(self.x + i, self.y, self.horizontal_border)?;
-            buffer.draw_char(self.x + i, self.y + self.height - 1, self.horizontal_border)?;
+            buffer.draw_char_styled(self.x + i, self.y, self.horizontal_border, self.style)?;
+            buffer.draw_char_styled(self.x + i, bottom, self.horizontal_border, self.style)?;
         }
         for i in 1..self.height - 1 {
-            buffer.draw_char(self.x, self.y + i, self.vertical_border)?;
-            buffer.draw_char(self.x + self.width - 1, self.y + i, self.vertical_border)?;
+            buffer.draw_char_styled(self.x, self.y + i, self.vertical_border, self.style)?;
+            buffer.draw_char_styled(right, self.y + i, self.vertical_border, self.style)?;
         }
 
-        for elem in self.sub_elements.values() {
-            elem.borrow().draw(buffer)?;
-        }
-        Ok(())
+        self.sub_elements.draw(buffer, self.bounds())
     }
     fn set_position(&mut self, x: usize, y: usize) {
-        for sub in self.sub_elements.values_mut() {
-            let (sub_x, sub_y) = sub.borrow().get_position();
-            let diff_x = self.x.abs_diff(sub_x);
-            let diff_y = self.y.abs_diff(sub_y);
-
-            sub.borrow_mut().set_position(x + diff_x, y + diff_y)
-        }
-
         self.x = x;
         self.y = y;
     }
     fn get_position(&self) -> (usize, usize) {
         (self.x, self.y)
     }
-    fn add_sub_element(&mut self, id: String, element: Rc<RefCell<dyn UIElement>>) {
-        let (x, y) = element.borrow().get_position();
-        element.borrow_mut().set_position(x + self.x, y + self.y);
+    fn get_size(&self) -> (usize, usize) {
+        (self.width, self.height)
+    }
+    fn set_size(&mut self, width: usize, height: usize) {
+        self.width = width;
+        self.height = height;
+    }
+    fn preferred_size(&self) -> (usize, usize) {
+        self.preferred
+    }
+    fn min_size(&self) -> (usize, usize) {
+        // Enough room for the corners.
+        (self.preferred.0.min(2), self.preferred.1.min(2))
+    }
+    fn add_sub_element(
+        &mut self,
+        id: String,
+        element: Rc<RefCell<dyn UIElement>>,
+    ) -> Result<(), Error> {
+        check_id(&id)?;
         self.sub_elements.insert(id, element);
+        Ok(())
     }
-    fn remove_sub_element(&mut self, id: &str) {
+    fn remove_sub_element(&mut self, id: &str) -> Result<(), Error> {
         self.sub_elements.remove(id);
+        Ok(())
+    }
+    fn get_sub_element(&self, id: &str) -> Option<Rc<RefCell<dyn UIElement>>> {
+        self.sub_elements.get(id).cloned()
+    }
+    fn sub_element_ids(&self) -> Vec<String> {
+        self.sub_elements.ids().cloned().collect()
     }
 }
 
@@ -135,6 +166,16 @@ mod tests {
         assert_eq!(rect.horizontal_border, '-');
     }
 
+    #[test]
+    fn test_size_and_bounds() {
+        let mut rect = Rectangle::new(1, 2, 4, 3);
+        assert_eq!(rect.get_size(), (4, 3));
+        rect.set_size(6, 1);
+        assert_eq!(rect.bounds(), crate::Rect::new(1, 2, 6, 1));
+        // The preferred size is still the one it was created with.
+        assert_eq!(rect.preferred_size(), (4, 3));
+    }
+
     #[test]
     fn test_rectangle_draw() {
         let rect = Rectangle::new(1, 1, 4, 3)
@@ -146,15 +187,52 @@ mod tests {
             .horizontal_border('-');
 
         let mut buffer = Buffer::new(10, 5);
-        rect.draw(&mut buffer);
+        rect.draw(&mut buffer).unwrap();
         let content = buffer.get_content();
 
-        assert_eq!(content[1 + 1 * 10], 'a');
-        assert_eq!(content[4 + 1 * 10], 'b');
+        assert_eq!(content[1 + 10], 'a');
+        assert_eq!(content[4 + 10], 'b');
         assert_eq!(content[1 + 3 * 10], 'c');
         assert_eq!(content[4 + 3 * 10], 'd');
     }
 
+    #[test]
+    fn test_rectangle_draw_styled() -> Result<(), Error> {
+        let style = Style::new().fg(crate::Color::Green);
+        let rect = Rectangle::new(0, 0, 3, 3)
+            .horizontal_border('-')
+            .style(style);
+
+        let mut buffer = Buffer::new(5, 5);
+        rect.draw(&mut buffer)?;
+
+        assert_eq!(buffer.get_cell(1, 0).unwrap().symbol, "-");
+        assert_eq!(buffer.get_cell(1, 0).unwrap().style, style);
+        assert_eq!(buffer.get_cell(1, 1).unwrap().style, Style::default());
+        Ok(())
+    }
+
+    #[test]
+    fn test_rectangle_draw_off_screen() -> Result<(), Error> {
+        let rect = Rectangle::new(3, 0, 4, 2).top_left('a').top_right('b');
+        let mut buffer = Buffer::new(5, 1);
+        rect.draw(&mut buffer)?;
+        assert_eq!(buffer.get_content(), vec![' ', ' ', ' ', 'a', ' ']);
+        Ok(())
+    }
+
+    #[test]
+    fn test_sub_elements_are_clipped() -> Result<(), Error> {
+        let mut rect = Rectangle::new(0, 0, 3, 1);
+        let text = Rc::new(RefCell::new(crate::Text::new(1, 0, "Hello")));
+        rect.add_sub_element("text".to_string(), text)?;
+
+        let mut buffer = Buffer::new(6, 1);
+        rect.draw(&mut buffer)?;
+        assert_eq!(buffer.get_content(), vec![' ', 'H', 'e', ' ', ' ', ' ']);
+        Ok(())
+    }
+
     #[test]
     fn test_set_position() {
         let mut rect = Rectangle::new(1, 1, 4, 3);
@@ -0,0 +1,447 @@
+use std::{cell::RefCell, rc::Rc};
+
+use crate::{
+    tree::check_id, Buffer, ElementList, Error, Event, KeyCode, KeyModifiers, MouseEventKind, Rect,
+    Style, UIElement,
+};
+
+/// Rows scrolled by one step of the mouse wheel.
+const WHEEL_STEP: usize = 3;
+
+/// Shows a window into content larger than itself.
+///
+/// Sub elements are positioned relative to the top left corner of the content,
+/// which is as large as needed to hold all of them unless set with
+/// [`ScrollView::content_size`]. Content scrolled out of view is clipped, even in strict mode.
+///
+/// The view can be drawn inside a border, the scrollbar then takes its right edge.
+pub struct ScrollView {
+    x: usize,
+    y: usize,
+    width: usize,
+    height: usize,
+    /// The size given at construction, layouts may change `width` and `height`.
+    preferred: (usize, usize),
+    offset: (usize, usize),
+    content_size: Option<(usize, usize)>,
+    scrollbar: bool,
+    border: bool,
+    focusable: bool,
+    style: Style,
+    sub_elements: ElementList,
+}
+
+impl ScrollView {
+    pub fn new(x: usize, y: usize, width: usize, height: usize) -> Self {
+        Self {
+            x,
+            y,
+            width,
+            height,
+            preferred: (width, height),
+            offset: (0, 0),
+            content_size: None,
+            scrollbar: false,
+            border: false,
+            focusable: true,
+            style: Style::default(),
+            sub_elements: ElementList::new(),
+        }
+    }
+
+    /// Draws a vertical scrollbar on the right edge of the border, or without a
+    /// border in the rightmost column, the content is then shown to the left of it.
+    pub fn scrollbar(mut self, scrollbar: bool) -> Self {
+        self.scrollbar = scrollbar;
+        self
+    }
+    /// Draws a border like a [`crate::Rectangle`] with box drawing characters around the content.
+    pub fn border(mut self, border: bool) -> Self {
+        self.border = border;
+        self.clamp_offset();
+        self
+    }
+    /// Whether the view takes the focus, so that it can be scrolled with the keyboard.
+    /// Defaults to `true`, views of focusable elements may not need it.
+    pub fn focusable(mut self, focusable: bool) -> Self {
+        self.focusable = focusable;
+        self
+    }
+    /// The style of the scrollbar.
+    pub fn style(mut self, style: Style) -> Self {
+        self.style = style;
+        self
+    }
+    /// Sets the size of the content instead of measuring the sub elements, e.g.
+    /// for content that is drawn lazily.
+    pub fn content_size(mut self, width: usize, height: usize) -> Self {
+        self.set_content_size(Some((width, height)));
+        self
+    }
+
+    pub fn set_content_size(&mut self, size: Option<(usize, usize)>) {
+        self.content_size = size;
+        self.clamp_offset();
+    }
+
+    /// The (width, height) of the content.
+    pub fn get_content_size(&self) -> (usize, usize) {
+        self.content_size.unwrap_or_else(|| {
+            self.sub_elements
+                .values()
+                .fold((0, 0), |(width, height), sub| {
+                    let bounds = sub.borrow().bounds();
+                    (width.max(bounds.right()), height.max(bounds.bottom()))
+                })
+        })
+    }
+
+    /// Whether the border is drawn, it needs room for all four edges.
+    fn has_border(&self) -> bool {
+        self.border && self.width >= 2 && self.height >= 2
+    }
+
+    /// The area of the content that is visible, relative to the scroll view.
+    fn viewport(&self) -> Rect {
+        if self.has_border() {
+            return Rect::new(1, 1, self.width - 2, self.height - 2);
+        }
+        let scrollbar = usize::from(self.scrollbar && self.width > 0);
+        Rect::new(0, 0, self.width - scrollbar, self.height)
+    }
+
+    /// The largest (x, y) offset that still fills the viewport.
+    pub fn max_offset(&self) -> (usize, usize) {
+        let (width, height) = self.get_content_size();
+        let viewport = self.viewport();
+        (
+            width.saturating_sub(viewport.width),
+            height.saturating_sub(viewport.height),
+        )
+    }
+
+    /// The (x, y) position of the content shown in the top left corner.
+    pub fn scroll_offset(&self) -> (usize, usize) {
+        self.offset
+    }
+
+    /// Scrolls to the given position of the content, as far as possible.
+    pub fn scroll_to(&mut self, x: usize, y: usize) {
+        self.offset = (x, y);
+        self.clamp_offset();
+    }
+
+    pub fn scroll_by(&mut self, x: isize, y: isize) {
+        let x = self.offset.0.saturating_add_signed(x);
+        let y = self.offset.1.saturating_add_signed(y);
+        self.scroll_to(x, y);
+    }
+
+    pub fn scroll_to_top(&mut self) {
+        self.scroll_to(self.offset.0, 0);
+    }
+
+    pub fn scroll_to_bottom(&mut self) {
+        self.scroll_to(self.offset.0, usize::MAX);
+    }
+
+    /// The offset within the current bounds, sub elements may have shrunk since it was set.
+    fn clamped_offset(&self) -> (usize, usize) {
+        let (max_x, max_y) = self.max_offset();
+        (self.offset.0.min(max_x), self.offset.1.min(max_y))
+    }
+
+    fn clamp_offset(&mut self) {
+        self.offset = self.clamped_offset();
+    }
+
+    /// The rows of the scrollbar covered by the thumb, relative to the viewport.
+    fn thumb(&self) -> (usize, usize) {
+        let track = self.viewport().height;
+        let (_, content) = self.get_content_size();
+        let (_, max) = self.max_offset();
+        if content <= track || max == 0 {
+            return (0, track);
+        }
+        let size = (track * track / content).clamp(1, track);
+        let start = self.offset.1 * (track - size) / max;
+        (start, size)
+    }
+
+    fn draw_border(&self, buffer: &mut Buffer) -> Result<(), Error> {
+        let (right, bottom) = (self.x + self.width - 1, self.y + self.height - 1);
+        buffer.draw_char_styled(self.x, self.y, '┌', self.style)?;
+        buffer.draw_char_styled(right, self.y, '┐', self.style)?;
+        buffer.draw_char_styled(self.x, bottom, '└', self.style)?;
+        buffer.draw_char_styled(right, bottom, '┘', self.style)?;
+        for x in self.x + 1..right {
+            buffer.draw_char_styled(x, self.y, '─', self.style)?;
+            buffer.draw_char_styled(x, bottom, '─', self.style)?;
+        }
+        for y in self.y + 1..bottom {
+            buffer.draw_char_styled(self.x, y, '│', self.style)?;
+            buffer.draw_char_styled(right, y, '│', self.style)?;
+        }
+        Ok(())
+    }
+
+    fn draw_scrollbar(&self, buffer: &mut Buffer) -> Result<(), Error> {
+        let x = self.x + self.width - 1;
+        let viewport = self.viewport();
+        let (start, size) = self.thumb();
+        for row in 0..viewport.height {
+            let symbol = if (start..start + size).contains(&row) {
+                '█'
+            } else {
+                '│'
+            };
+            buffer.draw_char_styled(x, self.y + viewport.y + row, symbol, self.style)?;
+        }
+        Ok(())
+    }
+}
+
+impl UIElement for ScrollView {
+    fn draw(&self, buffer: &mut Buffer) -> Result<(), Error> {
+        if self.width == 0 || self.height == 0 {
+            return Ok(());
+        }
+        if self.has_border() {
+            self.draw_border(buffer)?;
+        }
+        if self.scrollbar {
+            self.draw_scrollbar(buffer)?;
+        }
+
+        let viewport = self.viewport();
+        buffer.push_viewport(Rect::new(
+            self.x + viewport.x,
+            self.y + viewport.y,
+            viewport.width,
+            viewport.height,
+        ));
+        let (x, y) = self.clamped_offset();
+        buffer.push_scroll(x, y);
+        // The content is expected to overflow, hidden parts are not an error.
+        let strict = buffer.is_strict();
+        buffer.set_strict(false);
+        let result = self
+            .sub_elements
+            .values()
+            .try_for_each(|elem| elem.borrow().draw(buffer));
+        buffer.set_strict(strict);
+        buffer.pop_viewport();
+        buffer.pop_viewport();
+        result
+    }
+    fn set_position(&mut self, x: usize, y: usize) {
+        self.x = x;
+        self.y = y;
+    }
+    fn get_position(&self) -> (usize, usize) {
+        (self.x, self.y)
+    }
+    fn get_size(&self) -> (usize, usize) {
+        (self.width, self.height)
+    }
+    fn set_size(&mut self, width: usize, height: usize) {
+        self.width = width;
+        self.height = height;
+        self.clamp_offset();
+    }
+    fn preferred_size(&self) -> (usize, usize) {
+        self.preferred
+    }
+    fn add_sub_element(
+        &mut self,
+        id: String,
+        element: Rc<RefCell<dyn UIElement>>,
+    ) -> Result<(), Error> {
+        check_id(&id)?;
+        self.sub_elements.insert(id, element);
+        Ok(())
+    }
+    fn remove_sub_element(&mut self, id: &str) -> Result<(), Error> {
+        self.sub_elements.remove(id);
+        self.clamp_offset();
+        Ok(())
+    }
+    fn get_sub_element(&self, id: &str) -> Option<Rc<RefCell<dyn UIElement>>> {
+        self.sub_elements.get(id).cloned()
+    }
+    fn sub_element_ids(&self) -> Vec<String> {
+        self.sub_elements.ids().cloned().collect()
+    }
+    fn content_offset(&self) -> (isize, isize) {
+        let (x, y) = self.clamped_offset();
+        let viewport = self.viewport();
+        (
+            x as isize - viewport.x as isize,
+            y as isize - viewport.y as isize,
+        )
+    }
+    fn is_focusable(&self) -> bool {
+        self.focusable
+    }
+    /// Scrolls with the arrow keys, Page Up/Down, Home/End and the mouse wheel.
+    /// Keys with modifiers are left to the parents.
+    fn handle_event(&mut self, event: &Event) -> bool {
+        let page = self.viewport().height.max(1) as isize;
+        match event {
+            Event::Key {
+                code,
+                modifiers: KeyModifiers::NONE,
+            } => match code {
+                KeyCode::Up => self.scroll_by(0, -1),
+                KeyCode::Down => self.scroll_by(0, 1),
+                KeyCode::Left => self.scroll_by(-1, 0),
+                KeyCode::Right => self.scroll_by(1, 0),
+                KeyCode::PageUp => self.scroll_by(0, -page),
+                KeyCode::PageDown => self.scroll_by(0, page),
+                KeyCode::Home => self.scroll_to_top(),
+                KeyCode::End => self.scroll_to_bottom(),
+                _ => return false,
+            },
+            Event::Mouse { kind, .. } => match kind {
+                MouseEventKind::ScrollUp => self.scroll_by(0, -(WHEEL_STEP as isize)),
+                MouseEventKind::ScrollDown => self.scroll_by(0, WHEEL_STEP as isize),
+                MouseEventKind::ScrollLeft => self.scroll_by(-(WHEEL_STEP as isize), 0),
+                MouseEventKind::ScrollRight => self.scroll_by(WHEEL_STEP as isize, 0),
+                _ => return false,
+            },
+            _ => return false,
+        }
+        // Scrolling past the end of the content still counts as handled, so
+        // that the event doesn't scroll something else.
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{MouseButton, Text};
+
+    fn log(lines: usize) -> Rc<RefCell<Text>> {
+        let content: Vec<String> = (0..lines).map(|i| format!("line {i}")).collect();
+        Rc::new(RefCell::new(Text::new(0, 0, &content.join("\n"))))
+    }
+
+    fn row(buffer: &Buffer, y: usize) -> String {
+        (0..buffer.area().width)
+            .map(|x| buffer.get_cell(x, y).unwrap().symbol.as_str())
+            .collect()
+    }
+
+    #[test]
+    fn test_scroll_offset_is_clamped() -> Result<(), Error> {
+        let mut view = ScrollView::new(0, 0, 6, 3);
+        view.add_sub_element("log".to_string(), log(10))?;
+        assert_eq!(view.get_content_size(), (6, 10));
+        assert_eq!(view.max_offset(), (0, 7));
+
+        view.scroll_to(3, 20);
+        assert_eq!(view.scroll_offset(), (0, 7));
+        view.scroll_by(0, -10);
+        assert_eq!(view.scroll_offset(), (0, 0));
+        Ok(())
+    }
+
+    #[test]
+    fn test_draw_scrolled_content() -> Result<(), Error> {
+        let mut view = ScrollView::new(1, 1, 6, 2);
+        view.add_sub_element("log".to_string(), log(10))?;
+        view.scroll_to(0, 4);
+
+        let mut buffer = Buffer::new(8, 4);
+        buffer.set_strict(true);
+        view.draw(&mut buffer)?;
+        assert_eq!(row(&buffer, 0), "        ");
+        assert_eq!(row(&buffer, 1), " line 4 ");
+        assert_eq!(row(&buffer, 2), " line 5 ");
+        assert_eq!(row(&buffer, 3), "        ");
+        assert!(buffer.is_strict());
+        Ok(())
+    }
+
+    #[test]
+    fn test_scrollbar() -> Result<(), Error> {
+        let mut view = ScrollView::new(0, 0, 7, 4).scrollbar(true);
+        view.add_sub_element("log".to_string(), log(8))?;
+        view.scroll_to_bottom();
+        assert_eq!(view.scroll_offset(), (0, 4));
+
+        let mut buffer = Buffer::new(7, 4);
+        view.draw(&mut buffer)?;
+        assert_eq!(row(&buffer, 0), "line 4│");
+        assert_eq!(row(&buffer, 1), "line 5│");
+        assert_eq!(row(&buffer, 2), "line 6█");
+        assert_eq!(row(&buffer, 3), "line 7█");
+        Ok(())
+    }
+
+    #[test]
+    fn test_scrollbar_on_border() -> Result<(), Error> {
+        let mut view = ScrollView::new(0, 0, 8, 5).border(true).scrollbar(true);
+        view.add_sub_element("log".to_string(), log(6))?;
+        assert_eq!(view.max_offset(), (0, 3));
+        view.scroll_to_bottom();
+
+        let mut buffer = Buffer::new(8, 5);
+        view.draw(&mut buffer)?;
+        assert_eq!(row(&buffer, 0), "┌──────┐");
+        assert_eq!(row(&buffer, 1), "│line 3│");
+        assert_eq!(row(&buffer, 2), "│line 4│");
+        assert_eq!(row(&buffer, 3), "│line 5█");
+        assert_eq!(row(&buffer, 4), "└──────┘");
+        Ok(())
+    }
+
+    #[test]
+    fn test_virtual_content_size() {
+        let mut view = ScrollView::new(0, 0, 10, 5).content_size(10, 100);
+        view.scroll_to_bottom();
+        assert_eq!(view.scroll_offset(), (0, 95));
+    }
+
+    #[test]
+    fn test_focusable() {
+        assert!(ScrollView::new(0, 0, 6, 3).is_focusable());
+        assert!(!ScrollView::new(0, 0, 6, 3).focusable(false).is_focusable());
+    }
+
+    #[test]
+    fn test_keys_and_wheel() -> Result<(), Error> {
+        let mut view = ScrollView::new(0, 0, 6, 3);
+        view.add_sub_element("log".to_string(), log(20))?;
+        let key = |code| Event::key(code, KeyModifiers::NONE);
+        let wheel = |kind| Event::Mouse {
+            kind,
+            button: MouseButton::None,
+            x: 0,
+            y: 0,
+            modifiers: KeyModifiers::NONE,
+        };
+
+        assert!(view.handle_event(&key(KeyCode::Down)));
+        assert_eq!(view.scroll_offset(), (0, 1));
+        assert!(view.handle_event(&key(KeyCode::PageDown)));
+        assert_eq!(view.scroll_offset(), (0, 4));
+        assert!(view.handle_event(&wheel(MouseEventKind::ScrollDown)));
+        assert_eq!(view.scroll_offset(), (0, 7));
+        assert!(view.handle_event(&wheel(MouseEventKind::ScrollUp)));
+        assert!(view.handle_event(&key(KeyCode::PageUp)));
+        assert_eq!(view.scroll_offset(), (0, 1));
+        assert!(view.handle_event(&key(KeyCode::End)));
+        assert_eq!(view.scroll_offset(), (0, 17));
+        assert!(view.handle_event(&key(KeyCode::Home)));
+        assert_eq!(view.scroll_offset(), (0, 0));
+
+        assert!(!view.handle_event(&key(KeyCode::Enter)));
+        assert!(!view.handle_event(&Event::key(KeyCode::End, KeyModifiers::CTRL)));
+        assert!(!view.handle_event(&Event::key(KeyCode::Left, KeyModifiers::ALT)));
+        assert_eq!(view.scroll_offset(), (0, 0));
+        assert!(!view.handle_event(&Event::FocusGained));
+        Ok(())
+    }
+}
@@ -1,9 +1,74 @@
-use crate::{Buffer, Error, UIElement};
+use std::ops::Range;
 
+use unicode_segmentation::UnicodeSegmentation;
+
+use crate::{
+    buffer::{str_width, symbol_width},
+    Buffer, Error, Line, Style, UIElement,
+};
+
+const ELLIPSIS: &str = "…";
+
+/// How lines longer than the width of a [`Text`] are broken.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Wrap {
+    /// Lines are cut at the edge.
+    #[default]
+    None,
+    /// Lines are broken between words, words longer than the width are broken anywhere.
+    Word,
+    /// Lines are broken at the last character that fits.
+    Char,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Alignment {
+    #[default]
+    Left,
+    Center,
+    Right,
+    /// Spreads the words over the whole width, except on the last line of a paragraph.
+    Justify,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum VerticalAlignment {
+    #[default]
+    Top,
+    Middle,
+    Bottom,
+}
+
+/// Text drawn line by line, `\n` starts a new line.
+///
+/// The content is either a plain string drawn with the style of the text, or
+/// lines of [`crate::Span`]s whose styles are applied on top of it.
+///
+/// Without a size the text is as large as its content. Once sized, with
+/// [`Text::size`] or by a layout, lines are wrapped, aligned and cut to fit into the box.
 pub struct Text {
     x: usize,
     y: usize,
+    /// The lines of the content, separated by `\n`.
     content: String,
+    /// The style of each span, by byte range of `content`.
+    spans: Vec<(Range<usize>, Style)>,
+    style: Style,
+    size: Option<(usize, usize)>,
+    wrap: Wrap,
+    align: Alignment,
+    vertical_align: VerticalAlignment,
+    ellipsis: bool,
+}
+
+/// A row of laid out text.
+struct Row {
+    /// The part of the content shown on the row.
+    range: Range<usize>,
+    /// Whether the row was cut and ends with an ellipsis.
+    ellipsis: bool,
+    /// Whether the row can be justified, i.e. it isn't the last of its paragraph.
+    justify: bool,
 }
 
 impl Text {
@@ -12,18 +77,273 @@ impl Text {
             x,
             y,
             content: content.to_string(),
+            spans: vec![(0..content.len(), Style::default())],
+            style: Style::default(),
+            size: None,
+            wrap: Wrap::default(),
+            align: Alignment::default(),
+            vertical_align: VerticalAlignment::default(),
+            ellipsis: false,
         }
     }
 
+    pub fn style(mut self, style: Style) -> Self {
+        self.style = style;
+        self
+    }
+    /// Draws the text into a box of the given size instead of sizing it to the content.
+    pub fn size(mut self, width: usize, height: usize) -> Self {
+        self.size = Some((width, height));
+        self
+    }
+    pub fn wrap(mut self, wrap: Wrap) -> Self {
+        self.wrap = wrap;
+        self
+    }
+    pub fn align(mut self, align: Alignment) -> Self {
+        self.align = align;
+        self
+    }
+    pub fn vertical_align(mut self, vertical_align: VerticalAlignment) -> Self {
+        self.vertical_align = vertical_align;
+        self
+    }
+    /// Ends lines that are cut, and the last line if there are more than fit, with `…`.
+    pub fn ellipsis(mut self, ellipsis: bool) -> Self {
+        self.ellipsis = ellipsis;
+        self
+    }
+
+    /// Creates a text from lines of styled spans.
+    pub fn from_lines(x: usize, y: usize, lines: Vec<Line>) -> Self {
+        let mut text = Self::new(x, y, "");
+        text.set_lines(lines);
+        text
+    }
+
+    /// Replaces the content with a plain string.
     pub fn set_content(&mut self, content: &str) {
         self.content = content.to_string();
+        self.spans = vec![(0..content.len(), Style::default())];
+    }
+
+    /// Replaces the content with lines of styled spans.
+    pub fn set_lines(&mut self, lines: Vec<Line>) {
+        self.content.clear();
+        self.spans.clear();
+        for (i, line) in lines.into_iter().enumerate() {
+            if i > 0 {
+                self.content.push('\n');
+            }
+            for span in line.spans {
+                let start = self.content.len();
+                self.content.push_str(&span.content);
+                self.spans.push((start..self.content.len(), span.style));
+            }
+        }
+    }
+
+    /// The lines of the content, with the byte offset each of them starts at.
+    fn paragraphs(&self) -> impl Iterator<Item = (usize, &str)> {
+        self.content.split('\n').scan(0, |offset, line| {
+            let start = *offset;
+            *offset += line.len() + 1;
+            Some((start, line.strip_suffix('\r').unwrap_or(line)))
+        })
+    }
+
+    /// The (width, height) of the content, without wrapping.
+    fn content_size(&self) -> (usize, usize) {
+        self.paragraphs()
+            .fold((0, 0), |(width, height), (_, line)| {
+                (width.max(str_width(line)), height + 1)
+            })
+    }
+
+    /// Wraps and cuts the content to fit into the box.
+    fn rows(&self) -> Vec<Row> {
+        let mut rows = Vec::new();
+        for (offset, line) in self.paragraphs() {
+            let width = self.size.map_or(0, |(width, _)| width);
+            let ranges = wrap(line, width, self.wrap);
+            let count = ranges.len();
+            rows.extend(ranges.into_iter().enumerate().map(|(i, range)| Row {
+                range: offset + range.start..offset + range.end,
+                ellipsis: false,
+                justify: i + 1 < count,
+            }));
+        }
+
+        let Some((width, height)) = self.size else {
+            return rows;
+        };
+        if rows.len() > height {
+            rows.truncate(height);
+            if let Some(last) = rows.last_mut().filter(|_| self.ellipsis) {
+                last.ellipsis = true;
+                last.justify = false;
+            }
+        }
+        for row in rows.iter_mut() {
+            let text = &self.content[row.range.clone()];
+            if self.ellipsis && str_width(text) > width {
+                row.ellipsis = true;
+            }
+            row.ellipsis &= width > 0;
+            let limit = width - usize::from(row.ellipsis);
+            row.range.end = row.range.start + fit(text, limit);
+        }
+        rows
+    }
+
+    /// Draws the content in `range` at (x, y), span by span, and returns the number of columns used.
+    fn draw_range(
+        &self,
+        buffer: &mut Buffer,
+        x: usize,
+        y: usize,
+        range: Range<usize>,
+    ) -> Result<usize, Error> {
+        let mut used = 0;
+        for (span, style) in self.spans.iter() {
+            let start = span.start.max(range.start);
+            let end = span.end.min(range.end);
+            if start < end {
+                let content = &self.content[start..end];
+                used += buffer.draw_str(x + used, y, content, self.style.patch(*style))?;
+            }
+        }
+        Ok(used)
+    }
+
+    fn draw_justified(
+        &self,
+        buffer: &mut Buffer,
+        y: usize,
+        row: &Range<usize>,
+    ) -> Result<(), Error> {
+        let (width, _) = self.get_size();
+        let mut words = Vec::new();
+        let mut start = row.start;
+        for word in self.content[row.clone()].split(' ') {
+            if !word.is_empty() {
+                words.push(start..start + word.len());
+            }
+            start += word.len() + 1;
+        }
+        let gaps = words.len().saturating_sub(1);
+        let free = width.saturating_sub(
+            words
+                .iter()
+                .map(|word| str_width(&self.content[word.clone()]))
+                .sum(),
+        );
+
+        let mut x = self.x;
+        for (i, word) in words.into_iter().enumerate() {
+            x += self.draw_range(buffer, x, y, word)?;
+            if i < gaps {
+                x += free / gaps + usize::from(i < free % gaps);
+            }
+        }
+        Ok(())
     }
 }
 
+/// Breaks `line` into ranges no wider than `width`, a width of 0 doesn't wrap.
+fn wrap(line: &str, width: usize, mode: Wrap) -> Vec<Range<usize>> {
+    if mode == Wrap::None || width == 0 {
+        return std::iter::once(0..line.len()).collect();
+    }
+
+    let mut ranges = Vec::new();
+    let mut start = 0;
+    let mut used = 0;
+    // Where the last word on the row ends, where the next one starts and the width up to there.
+    let mut word_break: Option<(usize, usize, usize)> = None;
+    for (i, grapheme) in line.grapheme_indices(true) {
+        let grapheme_width = symbol_width(grapheme);
+        if mode == Wrap::Word && grapheme.chars().all(char::is_whitespace) {
+            // Whitespace at the start of a wrapped row is dropped.
+            if i == start && !ranges.is_empty() {
+                start = i + grapheme.len();
+                continue;
+            }
+            if used + grapheme_width > width {
+                // Whitespace at the end of a row is dropped.
+                ranges.push(start..i);
+                start = i + grapheme.len();
+                used = 0;
+                word_break = None;
+                continue;
+            }
+            used += grapheme_width;
+            let end = match word_break {
+                Some((end, next, _)) if next == i => end,
+                _ => i,
+            };
+            word_break = Some((end, i + grapheme.len(), used));
+            continue;
+        }
+
+        if used + grapheme_width > width && i > start {
+            if let Some((end, next, at)) = word_break.take() {
+                ranges.push(start..end);
+                start = next;
+                used -= at;
+            }
+            if used + grapheme_width > width && i > start {
+                ranges.push(start..i);
+                start = i;
+                used = 0;
+            }
+        }
+        used += grapheme_width;
+    }
+    ranges.push(start..line.len());
+    ranges
+}
+
+/// The length in bytes of the longest prefix of `text` that is at most `width` columns wide.
+fn fit(text: &str, width: usize) -> usize {
+    let mut used = 0;
+    for (i, grapheme) in text.grapheme_indices(true) {
+        used += symbol_width(grapheme);
+        if used > width {
+            return i;
+        }
+    }
+    text.len()
+}
+
 impl UIElement for Text {
     fn draw(&self, buffer: &mut Buffer) -> Result<(), Error> {
-        for (i, c) in self.content.chars().enumerate() {
-            buffer.draw_char(self.x + i, self.y, c)?;
+        let (width, height) = self.get_size();
+        let rows = self.rows();
+        let top = match self.vertical_align {
+            VerticalAlignment::Top => 0,
+            VerticalAlignment::Middle => height.saturating_sub(rows.len()) / 2,
+            VerticalAlignment::Bottom => height.saturating_sub(rows.len()),
+        };
+
+        for (i, row) in rows.iter().enumerate() {
+            let text = &self.content[row.range.clone()];
+            let y = self.y + top + i;
+            if self.align == Alignment::Justify && row.justify {
+                self.draw_justified(buffer, y, &row.range)?;
+                continue;
+            }
+
+            let free = width.saturating_sub(str_width(text) + usize::from(row.ellipsis));
+            let left = match self.align {
+                Alignment::Left | Alignment::Justify => 0,
+                Alignment::Center => free / 2,
+                Alignment::Right => free,
+            };
+            let used = self.draw_range(buffer, self.x + left, y, row.range.clone())?;
+            if row.ellipsis {
+                buffer.draw_str(self.x + left + used, y, ELLIPSIS, self.style)?;
+            }
         }
         Ok(())
     }
@@ -34,23 +354,21 @@ impl UIElement for Text {
     fn get_position(&self) -> (usize, usize) {
         (self.x, self.y)
     }
-
-    fn add_sub_element(
-        &mut self,
-        _id: String,
-        _element: std::rc::Rc<std::cell::RefCell<dyn UIElement>>,
-    ) {
-        todo!()
+    fn get_size(&self) -> (usize, usize) {
+        self.size.unwrap_or_else(|| self.content_size())
     }
-
-    fn remove_sub_element(&mut self, _id: &str) {
-        todo!()
+    fn set_size(&mut self, width: usize, height: usize) {
+        self.size = Some((width, height));
+    }
+    fn preferred_size(&self) -> (usize, usize) {
+        self.content_size()
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::{Attributes, Color, Rect, Span};
 
     #[test]
     fn test_text_new() {
@@ -76,19 +394,48 @@ mod tests {
     }
 
     #[test]
-    #[should_panic(expected = "index out of bounds")]
-    fn test_draw_text_off_screen() {
+    fn test_text_draw_styled() -> Result<(), Error> {
+        let style = Style::new().fg(crate::Color::Yellow);
+        let text = Text::new(0, 0, "Hi").style(style);
+        let mut buffer = Buffer::new(10, 5);
+        text.draw(&mut buffer)?;
+
+        assert_eq!(buffer.get_cell(0, 0).unwrap().style, style);
+        assert_eq!(buffer.get_cell(1, 0).unwrap().style, style);
+        assert_eq!(buffer.get_cell(2, 0).unwrap().style, Style::default());
+        Ok(())
+    }
+
+    #[test]
+    fn test_text_draw_wide() -> Result<(), Error> {
+        let text = Text::new(1, 0, "日本語!");
+        let mut buffer = Buffer::new(10, 1);
+        text.draw(&mut buffer)?;
+
+        assert_eq!(buffer.get_cell(1, 0).unwrap().symbol, "日");
+        assert_eq!(buffer.get_cell(3, 0).unwrap().symbol, "本");
+        assert_eq!(buffer.get_cell(5, 0).unwrap().symbol, "語");
+        assert_eq!(buffer.get_cell(7, 0).unwrap().symbol, "!");
+        Ok(())
+    }
+
+    #[test]
+    fn test_draw_text_off_screen() -> Result<(), Error> {
         let text = Text::new(2, 2, "Hello");
         let mut buffer = Buffer::new(5, 3);
-        text.draw(&mut buffer);
+        text.draw(&mut buffer)?;
 
-        let content = buffer.get_content();
-        // panic!("{:?}", content);
-        assert_eq!(content[2 + 2 * 5], 'H');
-        assert_eq!(content[3 + 2 * 5], 'e');
-        assert_eq!(content[4 + 2 * 5], 'l');
-        assert_eq!(content[5 + 2 * 5], 'l');
-        // assert_eq!(content[5 + 2 * 5], ' ');
+        let row: String = buffer.get_content()[2 * 5..].iter().collect();
+        assert_eq!(row, "  Hel");
+
+        // In strict mode drawing past the clip area of a parent fails instead.
+        buffer.set_strict(true);
+        buffer.push_clip(Rect::new(0, 0, 4, 3));
+        assert!(matches!(
+            text.draw(&mut buffer),
+            Err(Error::ClipError { x: 4, y: 2, .. })
+        ));
+        Ok(())
     }
 
     #[test]
@@ -98,6 +445,189 @@ mod tests {
         assert_eq!(text.content, "World".to_string());
     }
 
+    #[test]
+    fn test_size_from_content() {
+        let mut text = Text::new(2, 1, "日本x");
+        assert_eq!(text.get_size(), (5, 1));
+        assert_eq!(text.bounds(), crate::Rect::new(2, 1, 5, 1));
+
+        text.set_content("");
+        assert_eq!(text.get_size(), (0, 1));
+    }
+
+    #[test]
+    fn test_text_has_no_sub_elements() {
+        let mut text = Text::new(0, 0, "Hello");
+        let other = std::rc::Rc::new(std::cell::RefCell::new(Text::new(0, 0, "World")));
+        assert!(matches!(
+            text.add_sub_element("other".to_string(), other),
+            Err(Error::NotAContainer)
+        ));
+        assert!(matches!(
+            text.remove_sub_element("other"),
+            Err(Error::NotAContainer)
+        ));
+        assert!(text.sub_element_ids().is_empty());
+    }
+
+    fn rows(text: &Text, width: usize, height: usize) -> Vec<String> {
+        let mut buffer = Buffer::new(width, height);
+        text.draw(&mut buffer).unwrap();
+        (0..height)
+            .map(|y| {
+                (0..width)
+                    .map(|x| buffer.get_cell(x, y).unwrap().symbol.as_str())
+                    .collect()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_newlines() {
+        let text = Text::new(0, 0, "ab\r\nc\n\nd");
+        assert_eq!(text.get_size(), (2, 4));
+        assert_eq!(rows(&text, 3, 4), vec!["ab ", "c  ", "   ", "d  "]);
+    }
+
+    #[test]
+    fn test_wrap() {
+        assert_eq!(wrap("the quick fox", 9, Wrap::Word), vec![0..9, 10..13]);
+        assert_eq!(
+            wrap("the quick fox", 5, Wrap::Word),
+            vec![0..3, 4..9, 10..13]
+        );
+        assert_eq!(wrap("a  b", 1, Wrap::Word), vec![0..1, 3..4]);
+        assert_eq!(wrap("abcdefg h", 3, Wrap::Word), vec![0..3, 3..6, 6..9]);
+        assert_eq!(
+            wrap("abcdefg hi", 3, Wrap::Word),
+            vec![0..3, 3..6, 6..7, 8..10]
+        );
+        assert_eq!(wrap("abcdefg", 3, Wrap::Char), vec![0..3, 3..6, 6..7]);
+        assert_eq!(wrap("日本語", 3, Wrap::Char), vec![0..3, 3..6, 6..9]);
+        assert_eq!(wrap("abcdefg", 3, Wrap::None), vec![0..7]);
+    }
+
+    #[test]
+    fn test_word_wrap_in_box() {
+        let text = Text::new(0, 0, "the quick brown fox")
+            .size(10, 3)
+            .wrap(Wrap::Word);
+        assert_eq!(
+            rows(&text, 10, 3),
+            vec!["the quick ", "brown fox ", "          "]
+        );
+    }
+
+    #[test]
+    fn test_alignment() {
+        let text = |align| Text::new(0, 0, "ab\ncde").size(5, 2).align(align);
+        assert_eq!(rows(&text(Alignment::Left), 5, 2), vec!["ab   ", "cde  "]);
+        assert_eq!(rows(&text(Alignment::Center), 5, 2), vec![" ab  ", " cde "]);
+        assert_eq!(rows(&text(Alignment::Right), 5, 2), vec!["   ab", "  cde"]);
+    }
+
+    #[test]
+    fn test_justify() {
+        let text = Text::new(0, 0, "a bb c dd e")
+            .size(8, 2)
+            .wrap(Wrap::Word)
+            .align(Alignment::Justify);
+        // The last line of the paragraph is not justified.
+        assert_eq!(rows(&text, 8, 2), vec!["a  bb  c", "dd e    "]);
+    }
+
+    #[test]
+    fn test_vertical_alignment() {
+        let text = |align| Text::new(0, 0, "a").size(1, 3).vertical_align(align);
+        assert_eq!(
+            rows(&text(VerticalAlignment::Top), 1, 3),
+            vec!["a", " ", " "]
+        );
+        assert_eq!(
+            rows(&text(VerticalAlignment::Middle), 1, 3),
+            vec![" ", "a", " "]
+        );
+        assert_eq!(
+            rows(&text(VerticalAlignment::Bottom), 1, 3),
+            vec![" ", " ", "a"]
+        );
+    }
+
+    #[test]
+    fn test_truncation() {
+        let cut = Text::new(0, 0, "abcdef").size(4, 1);
+        assert_eq!(rows(&cut, 6, 1), vec!["abcd  "]);
+
+        let ellipsis = Text::new(0, 0, "abcdef\nab").size(4, 2).ellipsis(true);
+        assert_eq!(rows(&ellipsis, 4, 2), vec!["abc…", "ab  "]);
+
+        let lines = Text::new(0, 0, "ab\ncd\nef").size(4, 2).ellipsis(true);
+        assert_eq!(rows(&lines, 4, 2), vec!["ab  ", "cd… "]);
+
+        let wide = Text::new(0, 0, "日本語").size(4, 1).ellipsis(true);
+        assert_eq!(rows(&wide, 4, 1), vec!["日… "]);
+    }
+
+    #[test]
+    fn test_sized_by_layout() {
+        let mut text = Text::new(0, 0, "hello world").wrap(Wrap::Word);
+        assert_eq!(text.preferred_size(), (11, 1));
+        text.set_size(5, 2);
+        assert_eq!(text.get_size(), (5, 2));
+        assert_eq!(rows(&text, 5, 2), vec!["hello", "world"]);
+    }
+
+    #[test]
+    fn test_spans() -> Result<(), Error> {
+        let base = Style::new().bg(Color::Blue);
+        let red = Style::new().fg(Color::Red);
+        let bold = Style::new().attributes(Attributes::BOLD);
+        let text = Text::from_lines(
+            0,
+            0,
+            vec![
+                Line::new(vec![Span::raw("a"), Span::styled("bc", red)]),
+                Line::from(Span::styled("d", bold)),
+            ],
+        )
+        .style(base);
+        assert_eq!(text.get_size(), (3, 2));
+
+        let mut buffer = Buffer::new(3, 2);
+        text.draw(&mut buffer)?;
+        assert_eq!(buffer.get_cell(0, 0).unwrap().style, base);
+        assert_eq!(buffer.get_cell(1, 0).unwrap().style, base.patch(red));
+        assert_eq!(buffer.get_cell(2, 0).unwrap().style, base.patch(red));
+        assert_eq!(buffer.get_cell(0, 1).unwrap().symbol, "d");
+        assert_eq!(buffer.get_cell(0, 1).unwrap().style, base.patch(bold));
+        Ok(())
+    }
+
+    #[test]
+    fn test_spans_are_wrapped() -> Result<(), Error> {
+        let red = Style::new().fg(Color::Red);
+        let text = Text::from_lines(
+            0,
+            0,
+            vec![Line::new(vec![
+                Span::raw("find "),
+                Span::styled("match", red),
+                Span::raw(" here"),
+            ])],
+        )
+        .size(8, 2)
+        .wrap(Wrap::Word);
+        assert_eq!(rows(&text, 8, 2), vec!["find    ", "match   "]);
+
+        let text = text.size(12, 2).align(Alignment::Justify);
+        assert_eq!(rows(&text, 12, 2), vec!["find   match", "here        "]);
+        let mut buffer = Buffer::new(12, 2);
+        text.draw(&mut buffer)?;
+        assert_eq!(buffer.get_cell(7, 0).unwrap().style, red);
+        assert_eq!(buffer.get_cell(6, 0).unwrap().style, Style::default());
+        Ok(())
+    }
+
     #[test]
     fn test_set_position() {
         let mut text = Text::new(2, 2, "Hello");
@@ -0,0 +1,593 @@
+use unicode_segmentation::UnicodeSegmentation;
+
+use super::editing::{
+    next_grapheme, next_word, position_at_column, prev_grapheme, prev_word, TextCallback,
+};
+use crate::{
+    buffer::str_width, Buffer, Error, Event, KeyCode, KeyModifiers, MouseButton, MouseEventKind,
+    Style, UIElement,
+};
+
+/// The number of edits that can be undone.
+const HISTORY_LIMIT: usize = 100;
+
+#[derive(Clone)]
+struct Snapshot {
+    lines: Vec<String>,
+    cursor: (usize, usize),
+}
+
+/// Edits that are undone together while repeated, e.g. typing a word.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum EditKind {
+    Insert,
+    Delete,
+}
+
+/// A multi-line text editor.
+///
+/// Moves like [`crate::TextInput`], plus Up/Down, Page Up/Down and Ctrl+Home/End
+/// for the start and end of the text. Ctrl+Z undoes and Ctrl+Y redoes edits,
+/// typing is undone a word at a time. Tab is left to focus navigation.
+pub struct TextArea {
+    x: usize,
+    y: usize,
+    width: usize,
+    height: usize,
+    /// The size given at construction, layouts may change `width` and `height`.
+    preferred: (usize, usize),
+    lines: Vec<String>,
+    /// The row and the byte offset in that row.
+    cursor: (usize, usize),
+    /// The column Up and Down move to, kept across lines shorter than it.
+    column: Option<usize>,
+    /// The (column, row) of the content shown in the top left corner.
+    offset: (usize, usize),
+    undo: Vec<Snapshot>,
+    redo: Vec<Snapshot>,
+    last_edit: Option<EditKind>,
+    style: Style,
+    focused: bool,
+    on_change: Option<TextCallback>,
+}
+
+impl TextArea {
+    pub fn new(x: usize, y: usize, width: usize, height: usize) -> Self {
+        Self {
+            x,
+            y,
+            width,
+            height,
+            preferred: (width, height),
+            lines: vec![String::new()],
+            cursor: (0, 0),
+            column: None,
+            offset: (0, 0),
+            undo: Vec::new(),
+            redo: Vec::new(),
+            last_edit: None,
+            style: Style::default(),
+            focused: false,
+            on_change: None,
+        }
+    }
+
+    pub fn value(mut self, value: &str) -> Self {
+        self.set_value(value);
+        self
+    }
+    pub fn style(mut self, style: Style) -> Self {
+        self.style = style;
+        self
+    }
+    /// Called with the new value whenever an event changes it.
+    pub fn on_change(mut self, callback: impl FnMut(&str) + 'static) -> Self {
+        self.on_change = Some(Box::new(callback));
+        self
+    }
+
+    /// The text, with lines separated by `\n`.
+    pub fn get_value(&self) -> String {
+        self.lines.join("\n")
+    }
+    /// Replaces the text without calling `on_change` and clears the undo history.
+    /// The cursor moves to the start.
+    pub fn set_value(&mut self, value: &str) {
+        self.lines = vec![String::new()];
+        self.cursor = (0, 0);
+        self.insert(value);
+        self.cursor = (0, 0);
+        self.column = None;
+        self.offset = (0, 0);
+        self.undo.clear();
+        self.redo.clear();
+        self.last_edit = None;
+    }
+    pub fn lines(&self) -> &[String] {
+        &self.lines
+    }
+
+    /// The row of the cursor and its byte offset in that row.
+    pub fn cursor(&self) -> (usize, usize) {
+        self.cursor
+    }
+
+    pub fn is_focused(&self) -> bool {
+        self.focused
+    }
+
+    fn line(&self) -> &str {
+        &self.lines[self.cursor.0]
+    }
+
+    fn snapshot(&self) -> Snapshot {
+        Snapshot {
+            lines: self.lines.clone(),
+            cursor: self.cursor,
+        }
+    }
+
+    fn restore(&mut self, snapshot: Snapshot) {
+        self.lines = snapshot.lines;
+        self.cursor = snapshot.cursor;
+        self.column = None;
+        self.last_edit = None;
+        self.changed();
+    }
+
+    /// Reverts the last edit, returns false if there is nothing to undo.
+    pub fn undo(&mut self) -> bool {
+        let Some(snapshot) = self.undo.pop() else {
+            return false;
+        };
+        self.redo.push(self.snapshot());
+        self.restore(snapshot);
+        true
+    }
+
+    /// Reapplies the last undone edit, returns false if there is nothing to redo.
+    pub fn redo(&mut self) -> bool {
+        let Some(snapshot) = self.redo.pop() else {
+            return false;
+        };
+        self.undo.push(self.snapshot());
+        self.restore(snapshot);
+        true
+    }
+
+    /// Records the state before an edit, unless it continues the previous edit of the same kind.
+    fn begin_edit(&mut self, kind: Option<EditKind>) {
+        if kind.is_none() || kind != self.last_edit {
+            if self.undo.len() == HISTORY_LIMIT {
+                self.undo.remove(0);
+            }
+            self.undo.push(self.snapshot());
+        }
+        self.redo.clear();
+        self.last_edit = kind;
+    }
+
+    /// Inserts `text` at the cursor, `\r\n` and `\r` start new lines like `\n`.
+    fn insert(&mut self, text: &str) {
+        let text = text.replace("\r\n", "\n").replace('\r', "\n");
+        let (row, col) = self.cursor;
+        let rest = self.lines[row].split_off(col);
+        let mut row = row;
+        for (i, part) in text.split('\n').enumerate() {
+            if i > 0 {
+                row += 1;
+                self.lines.insert(row, String::new());
+            }
+            let part = part.chars().filter(|c| !c.is_control());
+            self.lines[row].extend(part);
+        }
+        self.cursor = (row, self.lines[row].len());
+        self.lines[row].push_str(&rest);
+    }
+
+    /// The position after the last character.
+    fn end(&self) -> (usize, usize) {
+        let row = self.lines.len() - 1;
+        (row, self.lines[row].len())
+    }
+
+    fn delete_backward(&mut self, word: bool) {
+        let (row, col) = self.cursor;
+        if col > 0 {
+            let line = self.line();
+            let start = if word {
+                prev_word(line, col)
+            } else {
+                prev_grapheme(line, col)
+            };
+            self.lines[row].replace_range(start..col, "");
+            self.cursor.1 = start;
+        } else if row > 0 {
+            let line = self.lines.remove(row);
+            self.cursor = (row - 1, self.lines[row - 1].len());
+            self.lines[row - 1].push_str(&line);
+        }
+    }
+
+    fn delete_forward(&mut self, word: bool) {
+        let (row, col) = self.cursor;
+        if col < self.line().len() {
+            let line = self.line();
+            let end = if word {
+                next_word(line, col)
+            } else {
+                next_grapheme(line, col)
+            };
+            self.lines[row].replace_range(col..end, "");
+        } else if row + 1 < self.lines.len() {
+            let line = self.lines.remove(row + 1);
+            self.lines[row].push_str(&line);
+        }
+    }
+
+    fn move_to(&mut self, row: usize, col: usize) {
+        self.cursor = (row, col);
+        self.column = None;
+        self.last_edit = None;
+        self.scroll_to_cursor();
+    }
+
+    /// Moves `rows` up or down, to the column the cursor was at before moving vertically.
+    fn move_vertically(&mut self, rows: isize) {
+        let column = self
+            .column
+            .unwrap_or_else(|| str_width(&self.line()[..self.cursor.1]));
+        let row = self
+            .cursor
+            .0
+            .saturating_add_signed(rows)
+            .min(self.lines.len() - 1);
+        self.move_to(row, position_at_column(&self.lines[row], column));
+        self.column = Some(column);
+    }
+
+    fn move_left(&mut self, word: bool) {
+        let (row, col) = self.cursor;
+        if col > 0 {
+            let line = self.line();
+            let col = if word {
+                prev_word(line, col)
+            } else {
+                prev_grapheme(line, col)
+            };
+            self.move_to(row, col);
+        } else if row > 0 {
+            self.move_to(row - 1, self.lines[row - 1].len());
+        }
+    }
+
+    fn move_right(&mut self, word: bool) {
+        let (row, col) = self.cursor;
+        if col < self.line().len() {
+            let line = self.line();
+            let col = if word {
+                next_word(line, col)
+            } else {
+                next_grapheme(line, col)
+            };
+            self.move_to(row, col);
+        } else if row + 1 < self.lines.len() {
+            self.move_to(row + 1, 0);
+        }
+    }
+
+    /// Scrolls as little as possible to show the cursor.
+    fn scroll_to_cursor(&mut self) {
+        let (row, col) = self.cursor;
+        let column = str_width(&self.line()[..col]);
+        let (x, y) = &mut self.offset;
+        for (offset, position, size) in [(x, column, self.width), (y, row, self.height)] {
+            if position < *offset {
+                *offset = position;
+            } else if position >= *offset + size {
+                *offset = (position + 1).saturating_sub(size);
+            }
+        }
+    }
+
+    fn changed(&mut self) {
+        self.scroll_to_cursor();
+        if self.on_change.is_some() {
+            let value = self.get_value();
+            if let Some(on_change) = self.on_change.as_mut() {
+                on_change(&value);
+            }
+        }
+    }
+
+    fn draw_line(&self, line: &str, y: usize, buffer: &mut Buffer) -> Result<(), Error> {
+        let (offset, width) = (self.offset.0, self.width);
+        let mut column = 0;
+        for grapheme in line.graphemes(true) {
+            if column >= offset + width {
+                break;
+            }
+            let grapheme_width = str_width(grapheme);
+            // Graphemes only partially scrolled into view are left out.
+            if column >= offset && column + grapheme_width <= offset + width {
+                buffer.draw_str(self.x + column - offset, y, grapheme, self.style)?;
+            }
+            column += grapheme_width;
+        }
+        Ok(())
+    }
+}
+
+impl UIElement for TextArea {
+    fn draw(&self, buffer: &mut Buffer) -> Result<(), Error> {
+        if self.width == 0 || self.height == 0 {
+            return Ok(());
+        }
+        let rows = self.lines.iter().skip(self.offset.1).take(self.height);
+        for (i, line) in rows.enumerate() {
+            self.draw_line(line, self.y + i, buffer)?;
+        }
+
+        if self.focused {
+            let (row, col) = self.cursor;
+            let column = str_width(&self.line()[..col]);
+            // The cursor may be out of view after a resize, until it moves again.
+            if let (Some(x), Some(y)) = (
+                column
+                    .checked_sub(self.offset.0)
+                    .filter(|&x| x < self.width),
+                row.checked_sub(self.offset.1).filter(|&y| y < self.height),
+            ) {
+                buffer.set_cursor(self.x + x, self.y + y);
+            }
+        }
+        Ok(())
+    }
+    fn set_position(&mut self, x: usize, y: usize) {
+        self.x = x;
+        self.y = y;
+    }
+    fn get_position(&self) -> (usize, usize) {
+        (self.x, self.y)
+    }
+    fn get_size(&self) -> (usize, usize) {
+        (self.width, self.height)
+    }
+    fn set_size(&mut self, width: usize, height: usize) {
+        self.width = width;
+        self.height = height;
+        self.scroll_to_cursor();
+    }
+    fn preferred_size(&self) -> (usize, usize) {
+        self.preferred
+    }
+    fn is_focusable(&self) -> bool {
+        true
+    }
+    fn set_focused(&mut self, focused: bool) {
+        self.focused = focused;
+    }
+    fn handle_event(&mut self, event: &Event) -> bool {
+        let (code, modifiers) = match event {
+            Event::Key { code, modifiers } => (*code, *modifiers),
+            Event::Paste(content) => {
+                self.begin_edit(None);
+                self.insert(content);
+                self.changed();
+                return true;
+            }
+            Event::Mouse {
+                kind: MouseEventKind::Press,
+                button: MouseButton::Left,
+                x,
+                y,
+                ..
+            } => {
+                let row = self.offset.1.saturating_add_signed(*y);
+                let row = row.min(self.lines.len() - 1);
+                let column = self.offset.0.saturating_add_signed(*x);
+                let col = position_at_column(&self.lines[row], column);
+                self.move_to(row, col);
+                return true;
+            }
+            _ => return false,
+        };
+        let ctrl = modifiers.contains(KeyModifiers::CTRL);
+        let word = ctrl || modifiers.contains(KeyModifiers::ALT);
+        let page = self.height.max(1) as isize;
+        match code {
+            KeyCode::Char('z') if modifiers == KeyModifiers::CTRL => {
+                self.undo();
+            }
+            KeyCode::Char('y') if modifiers == KeyModifiers::CTRL => {
+                self.redo();
+            }
+            KeyCode::Char(c) if !word => {
+                // Whitespace ends the word being typed, so it is undone on its own.
+                let kind = (!c.is_whitespace()).then_some(EditKind::Insert);
+                self.begin_edit(kind);
+                self.insert(c.encode_utf8(&mut [0; 4]));
+                self.changed();
+            }
+            KeyCode::Enter => {
+                self.begin_edit(None);
+                self.insert("\n");
+                self.changed();
+            }
+            KeyCode::Backspace => {
+                if self.cursor != (0, 0) {
+                    self.begin_edit(Some(EditKind::Delete));
+                    self.delete_backward(word);
+                    self.changed();
+                }
+            }
+            KeyCode::Delete => {
+                if self.cursor != self.end() {
+                    self.begin_edit(Some(EditKind::Delete));
+                    self.delete_forward(word);
+                    self.changed();
+                }
+            }
+            KeyCode::Left => self.move_left(word),
+            KeyCode::Right => self.move_right(word),
+            KeyCode::Up => self.move_vertically(-1),
+            KeyCode::Down => self.move_vertically(1),
+            KeyCode::PageUp => self.move_vertically(-page),
+            KeyCode::PageDown => self.move_vertically(page),
+            KeyCode::Home if ctrl => self.move_to(0, 0),
+            KeyCode::End if ctrl => {
+                let (row, col) = self.end();
+                self.move_to(row, col);
+            }
+            KeyCode::Home => self.move_to(self.cursor.0, 0),
+            KeyCode::End => self.move_to(self.cursor.0, self.line().len()),
+            _ => return false,
+        }
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn key(code: KeyCode) -> Event {
+        Event::key(code, KeyModifiers::NONE)
+    }
+
+    fn ctrl(c: char) -> Event {
+        Event::key(KeyCode::Char(c), KeyModifiers::CTRL)
+    }
+
+    fn type_str(area: &mut TextArea, content: &str) {
+        for c in content.chars() {
+            let code = if c == '\n' {
+                KeyCode::Enter
+            } else {
+                KeyCode::Char(c)
+            };
+            area.handle_event(&key(code));
+        }
+    }
+
+    fn row(buffer: &Buffer, y: usize) -> String {
+        (0..buffer.area().width)
+            .map(|x| buffer.get_cell(x, y).unwrap().symbol.as_str())
+            .collect()
+    }
+
+    #[test]
+    fn test_editing_lines() {
+        let mut area = TextArea::new(0, 0, 20, 5);
+        type_str(&mut area, "first\nsecond");
+        assert_eq!(area.lines(), ["first", "second"]);
+        assert_eq!(area.cursor(), (1, 6));
+
+        area.handle_event(&key(KeyCode::Home));
+        area.handle_event(&key(KeyCode::Backspace));
+        assert_eq!(area.get_value(), "firstsecond");
+        assert_eq!(area.cursor(), (0, 5));
+
+        area.handle_event(&key(KeyCode::Enter));
+        area.handle_event(&key(KeyCode::Up));
+        area.handle_event(&key(KeyCode::End));
+        area.handle_event(&key(KeyCode::Delete));
+        assert_eq!(area.get_value(), "firstsecond");
+
+        area.handle_event(&Event::Paste("a\r\nb".to_string()));
+        assert_eq!(area.get_value(), "firsta\nbsecond");
+    }
+
+    #[test]
+    fn test_vertical_movement_keeps_column() {
+        let mut area = TextArea::new(0, 0, 20, 5).value("long line\nab\nanother line");
+        area.handle_event(&Event::key(KeyCode::Right, KeyModifiers::CTRL));
+        area.handle_event(&key(KeyCode::Right));
+        assert_eq!(area.cursor(), (0, 5));
+
+        area.handle_event(&key(KeyCode::Down));
+        assert_eq!(area.cursor(), (1, 2));
+        area.handle_event(&key(KeyCode::Down));
+        assert_eq!(area.cursor(), (2, 5));
+
+        area.handle_event(&Event::key(KeyCode::Home, KeyModifiers::CTRL));
+        assert_eq!(area.cursor(), (0, 0));
+        area.handle_event(&key(KeyCode::Left));
+        assert_eq!(area.cursor(), (0, 0));
+        area.handle_event(&Event::key(KeyCode::End, KeyModifiers::CTRL));
+        assert_eq!(area.cursor(), (2, 12));
+        area.handle_event(&key(KeyCode::Right));
+        assert_eq!(area.cursor(), (2, 12));
+    }
+
+    #[test]
+    fn test_undo_redo() {
+        let mut area = TextArea::new(0, 0, 20, 5);
+        type_str(&mut area, "hello world");
+        area.handle_event(&key(KeyCode::Backspace));
+        area.handle_event(&key(KeyCode::Backspace));
+        assert_eq!(area.get_value(), "hello wor");
+
+        assert!(area.handle_event(&ctrl('z')));
+        assert_eq!(area.get_value(), "hello world");
+        area.handle_event(&ctrl('z'));
+        assert_eq!(area.get_value(), "hello ");
+        area.handle_event(&ctrl('z'));
+        assert_eq!(area.get_value(), "hello");
+        area.handle_event(&ctrl('z'));
+        assert_eq!(area.get_value(), "");
+        assert!(!area.undo());
+
+        area.handle_event(&ctrl('y'));
+        area.handle_event(&ctrl('y'));
+        assert_eq!(area.get_value(), "hello ");
+        assert_eq!(area.cursor(), (0, 6));
+
+        // A new edit drops what could have been redone.
+        type_str(&mut area, "there");
+        assert!(!area.redo());
+        area.handle_event(&ctrl('z'));
+        assert_eq!(area.get_value(), "hello ");
+    }
+
+    #[test]
+    fn test_on_change() {
+        let changes = std::rc::Rc::new(std::cell::Cell::new(0));
+        let counter = changes.clone();
+        let mut area = TextArea::new(0, 0, 20, 5)
+            .value("text")
+            .on_change(move |_| counter.set(counter.get() + 1));
+        area.handle_event(&key(KeyCode::Backspace));
+        area.handle_event(&key(KeyCode::Delete));
+        type_str(&mut area, "ab");
+        assert_eq!(changes.get(), 3);
+        assert!(!area.handle_event(&key(KeyCode::Tab)));
+    }
+
+    #[test]
+    fn test_draw_scrolls_to_cursor() -> Result<(), Error> {
+        let mut area = TextArea::new(1, 1, 4, 2).value("one\ntwo\nthree\nfour");
+        area.set_focused(true);
+        area.handle_event(&Event::key(KeyCode::End, KeyModifiers::CTRL));
+        area.handle_event(&key(KeyCode::Up));
+        area.handle_event(&key(KeyCode::End));
+        assert_eq!(area.cursor(), (2, 5));
+
+        let mut buffer = Buffer::new(6, 4);
+        buffer.set_strict(true);
+        area.draw(&mut buffer)?;
+        assert_eq!(row(&buffer, 0), "      ");
+        assert_eq!(row(&buffer, 1), " ree  ");
+        assert_eq!(row(&buffer, 2), " ur   ");
+        assert_eq!(buffer.cursor(), Some((4, 1)));
+
+        area.handle_event(&Event::mouse(
+            MouseEventKind::Press,
+            MouseButton::Left,
+            0,
+            1,
+        ));
+        assert_eq!(area.cursor(), (3, 2));
+        Ok(())
+    }
+}
@@ -0,0 +1,582 @@
+use std::ops::Range;
+
+use unicode_segmentation::UnicodeSegmentation;
+
+use super::editing::{
+    grapheme_count, next_grapheme, next_word, position_at_column, prev_grapheme, prev_word,
+    TextCallback,
+};
+use crate::{
+    buffer::str_width, Attributes, Buffer, Error, Event, KeyCode, KeyModifiers, MouseButton,
+    MouseEventKind, Style, UIElement,
+};
+
+/// A single line text field.
+///
+/// The cursor moves by grapheme with the arrow keys and by word with Ctrl or Alt
+/// held, Shift extends the selection and Ctrl+A selects everything. Text that
+/// doesn't fit is scrolled horizontally to keep the cursor visible, and while
+/// focused the terminal cursor is shown at the edit point.
+pub struct TextInput {
+    x: usize,
+    y: usize,
+    width: usize,
+    /// The width given at construction, layouts may change `width`.
+    preferred: usize,
+    value: String,
+    /// Byte offset of the cursor in `value`.
+    cursor: usize,
+    /// The other end of the selection, which spans from here to the cursor.
+    anchor: Option<usize>,
+    /// The first visible column.
+    offset: usize,
+    placeholder: String,
+    mask: Option<char>,
+    max_length: Option<usize>,
+    style: Style,
+    placeholder_style: Style,
+    selection_style: Style,
+    focused: bool,
+    on_change: Option<TextCallback>,
+    on_submit: Option<TextCallback>,
+}
+
+impl TextInput {
+    pub fn new(x: usize, y: usize, width: usize) -> Self {
+        Self {
+            x,
+            y,
+            width,
+            preferred: width,
+            value: String::new(),
+            cursor: 0,
+            anchor: None,
+            offset: 0,
+            placeholder: String::new(),
+            mask: None,
+            max_length: None,
+            style: Style::default(),
+            placeholder_style: Style::new().attributes(Attributes::DIM),
+            selection_style: Style::new().attributes(Attributes::REVERSE),
+            focused: false,
+            on_change: None,
+            on_submit: None,
+        }
+    }
+
+    pub fn value(mut self, value: &str) -> Self {
+        self.set_value(value);
+        self
+    }
+    /// Shown while the input is empty.
+    pub fn placeholder(mut self, placeholder: &str) -> Self {
+        self.placeholder = placeholder.to_string();
+        self
+    }
+    /// Shows every character as `•`, and makes word jumps go to the start or end.
+    pub fn password(mut self, password: bool) -> Self {
+        self.mask = password.then_some('•');
+        self
+    }
+    /// Shows every character as `mask`, like [`TextInput::password`].
+    pub fn mask(mut self, mask: char) -> Self {
+        self.mask = Some(mask);
+        self
+    }
+    /// The maximum number of characters, counted as grapheme clusters.
+    pub fn max_length(mut self, max_length: usize) -> Self {
+        self.max_length = Some(max_length);
+        self.set_value(&self.value.clone());
+        self
+    }
+    pub fn style(mut self, style: Style) -> Self {
+        self.style = style;
+        self
+    }
+    pub fn placeholder_style(mut self, style: Style) -> Self {
+        self.placeholder_style = style;
+        self
+    }
+    pub fn selection_style(mut self, style: Style) -> Self {
+        self.selection_style = style;
+        self
+    }
+    /// Called with the new value whenever an event changes it.
+    pub fn on_change(mut self, callback: impl FnMut(&str) + 'static) -> Self {
+        self.on_change = Some(Box::new(callback));
+        self
+    }
+    /// Called with the value when Enter is pressed.
+    pub fn on_submit(mut self, callback: impl FnMut(&str) + 'static) -> Self {
+        self.on_submit = Some(Box::new(callback));
+        self
+    }
+
+    pub fn get_value(&self) -> &str {
+        &self.value
+    }
+    /// Replaces the value without calling `on_change`, the cursor moves to the end.
+    pub fn set_value(&mut self, value: &str) {
+        self.value.clear();
+        self.cursor = 0;
+        self.anchor = None;
+        self.insert(value);
+        self.scroll_to_cursor();
+    }
+
+    /// The byte offset of the cursor in the value.
+    pub fn cursor(&self) -> usize {
+        self.cursor
+    }
+    /// The selected byte range of the value, if anything is selected.
+    pub fn selection(&self) -> Option<Range<usize>> {
+        let anchor = self.anchor?;
+        let range = anchor.min(self.cursor)..anchor.max(self.cursor);
+        (!range.is_empty()).then_some(range)
+    }
+    pub fn selected_text(&self) -> &str {
+        self.selection().map_or("", |range| &self.value[range])
+    }
+    pub fn select_all(&mut self) {
+        self.anchor = Some(0);
+        self.cursor = self.value.len();
+        self.scroll_to_cursor();
+    }
+
+    pub fn is_focused(&self) -> bool {
+        self.focused
+    }
+
+    /// The column `pos` is drawn at, relative to the start of the value.
+    fn column(&self, pos: usize) -> usize {
+        match self.mask {
+            Some(mask) => grapheme_count(&self.value[..pos]) * str_width(&mask.to_string()),
+            None => str_width(&self.value[..pos]),
+        }
+    }
+
+    fn position_at(&self, column: usize) -> usize {
+        match self.mask {
+            Some(mask) => {
+                let n = column / str_width(&mask.to_string()).max(1);
+                self.value
+                    .grapheme_indices(true)
+                    .nth(n)
+                    .map_or(self.value.len(), |(i, _)| i)
+            }
+            None => position_at_column(&self.value, column),
+        }
+    }
+
+    /// Scrolls as little as possible to show the cursor, keeping a column free
+    /// for it after the end of the value.
+    fn scroll_to_cursor(&mut self) {
+        if self.width == 0 {
+            self.offset = 0;
+            return;
+        }
+        let column = self.column(self.cursor);
+        let end = self.column(self.value.len()) + 1;
+        self.offset = self.offset.min(end.saturating_sub(self.width));
+        if column < self.offset {
+            self.offset = column;
+        } else if column >= self.offset + self.width {
+            self.offset = column + 1 - self.width;
+        }
+    }
+
+    fn move_to(&mut self, pos: usize, select: bool) {
+        if select {
+            self.anchor.get_or_insert(self.cursor);
+        } else {
+            self.anchor = None;
+        }
+        self.cursor = pos;
+        self.scroll_to_cursor();
+    }
+
+    fn prev_stop(&self, word: bool) -> usize {
+        match (word, self.mask) {
+            (true, Some(_)) => 0,
+            (true, None) => prev_word(&self.value, self.cursor),
+            (false, _) => prev_grapheme(&self.value, self.cursor),
+        }
+    }
+
+    fn next_stop(&self, word: bool) -> usize {
+        match (word, self.mask) {
+            (true, Some(_)) => self.value.len(),
+            (true, None) => next_word(&self.value, self.cursor),
+            (false, _) => next_grapheme(&self.value, self.cursor),
+        }
+    }
+
+    /// Returns whether anything was deleted.
+    fn delete_selection(&mut self) -> bool {
+        let selection = self.selection();
+        self.anchor = None;
+        let Some(range) = selection else {
+            return false;
+        };
+        self.cursor = range.start;
+        self.value.replace_range(range, "");
+        true
+    }
+
+    /// Replaces the selection with `text`, as far as the maximum length allows.
+    /// Returns whether the value changed.
+    fn insert(&mut self, text: &str) -> bool {
+        let deleted = self.delete_selection();
+        let text: String = text.chars().filter(|c| !c.is_control()).collect();
+        let mut end = text.len();
+        if let Some(max_length) = self.max_length {
+            let room = max_length.saturating_sub(grapheme_count(&self.value));
+            end = text
+                .grapheme_indices(true)
+                .nth(room)
+                .map_or(end, |(i, _)| i);
+        }
+        self.value.insert_str(self.cursor, &text[..end]);
+        self.cursor += end;
+        deleted || end > 0
+    }
+
+    fn delete_backward(&mut self, word: bool) -> bool {
+        if self.delete_selection() {
+            return true;
+        }
+        let start = self.prev_stop(word);
+        self.value.replace_range(start..self.cursor, "");
+        let deleted = start < self.cursor;
+        self.cursor = start;
+        deleted
+    }
+
+    fn delete_forward(&mut self, word: bool) -> bool {
+        if self.delete_selection() {
+            return true;
+        }
+        let end = self.next_stop(word);
+        self.value.replace_range(self.cursor..end, "");
+        end > self.cursor
+    }
+
+    fn changed(&mut self) {
+        self.scroll_to_cursor();
+        if let Some(on_change) = self.on_change.as_mut() {
+            on_change(&self.value);
+        }
+    }
+
+    fn draw_placeholder(&self, buffer: &mut Buffer) -> Result<(), Error> {
+        let end = position_at_column(&self.placeholder, self.width);
+        let style = self.style.patch(self.placeholder_style);
+        buffer.draw_str(self.x, self.y, &self.placeholder[..end], style)?;
+        Ok(())
+    }
+}
+
+impl UIElement for TextInput {
+    fn draw(&self, buffer: &mut Buffer) -> Result<(), Error> {
+        if self.width == 0 {
+            return Ok(());
+        }
+        if self.value.is_empty() {
+            self.draw_placeholder(buffer)?;
+        }
+
+        let selection = self.selection().unwrap_or_default();
+        let mut encoded = [0; 4];
+        let mask = self.mask.map(|mask| &*mask.encode_utf8(&mut encoded));
+        let mut column = 0;
+        for (i, grapheme) in self.value.grapheme_indices(true) {
+            let symbol = mask.unwrap_or(grapheme);
+            let width = str_width(symbol);
+            if column >= self.offset + self.width {
+                break;
+            }
+            // Graphemes only partially scrolled into view are left out.
+            if column >= self.offset && column + width <= self.offset + self.width {
+                let style = if selection.contains(&i) {
+                    self.style.patch(self.selection_style)
+                } else {
+                    self.style
+                };
+                buffer.draw_str(self.x + column - self.offset, self.y, symbol, style)?;
+            }
+            column += width;
+        }
+
+        if self.focused {
+            let column = self.column(self.cursor) - self.offset;
+            buffer.set_cursor(self.x + column, self.y);
+        }
+        Ok(())
+    }
+    fn set_position(&mut self, x: usize, y: usize) {
+        self.x = x;
+        self.y = y;
+    }
+    fn get_position(&self) -> (usize, usize) {
+        (self.x, self.y)
+    }
+    fn get_size(&self) -> (usize, usize) {
+        (self.width, 1)
+    }
+    fn set_size(&mut self, width: usize, _height: usize) {
+        self.width = width;
+        self.scroll_to_cursor();
+    }
+    fn preferred_size(&self) -> (usize, usize) {
+        (self.preferred, 1)
+    }
+    fn is_focusable(&self) -> bool {
+        true
+    }
+    fn set_focused(&mut self, focused: bool) {
+        self.focused = focused;
+    }
+    fn handle_event(&mut self, event: &Event) -> bool {
+        let (code, modifiers) = match event {
+            Event::Key { code, modifiers } => (*code, *modifiers),
+            Event::Paste(content) => {
+                if self.insert(content) {
+                    self.changed();
+                }
+                return true;
+            }
+            Event::Mouse {
+                kind: MouseEventKind::Press,
+                button: MouseButton::Left,
+                x,
+                ..
+            } => {
+                self.move_to(
+                    self.position_at(self.offset.saturating_add_signed(*x)),
+                    false,
+                );
+                return true;
+            }
+            Event::Mouse {
+                kind: MouseEventKind::Drag,
+                x,
+                ..
+            } => {
+                self.move_to(
+                    self.position_at(self.offset.saturating_add_signed(*x)),
+                    true,
+                );
+                return true;
+            }
+            _ => return false,
+        };
+        let shift = modifiers.contains(KeyModifiers::SHIFT);
+        let word = modifiers.contains(KeyModifiers::CTRL) || modifiers.contains(KeyModifiers::ALT);
+        let edited = match code {
+            KeyCode::Char('a') if modifiers == KeyModifiers::CTRL => {
+                self.select_all();
+                false
+            }
+            KeyCode::Char(c) if !word => self.insert(c.encode_utf8(&mut [0; 4])),
+            KeyCode::Backspace => self.delete_backward(word),
+            KeyCode::Delete => self.delete_forward(word),
+            KeyCode::Left => {
+                let pos = match self.selection() {
+                    Some(selection) if !shift => selection.start,
+                    _ => self.prev_stop(word),
+                };
+                self.move_to(pos, shift);
+                false
+            }
+            KeyCode::Right => {
+                let pos = match self.selection() {
+                    Some(selection) if !shift => selection.end,
+                    _ => self.next_stop(word),
+                };
+                self.move_to(pos, shift);
+                false
+            }
+            KeyCode::Home => {
+                self.move_to(0, shift);
+                false
+            }
+            KeyCode::End => {
+                self.move_to(self.value.len(), shift);
+                false
+            }
+            KeyCode::Enter => {
+                if let Some(on_submit) = self.on_submit.as_mut() {
+                    on_submit(&self.value);
+                }
+                false
+            }
+            _ => return false,
+        };
+        if edited {
+            self.changed();
+        }
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::{cell::RefCell, rc::Rc};
+
+    fn key(code: KeyCode) -> Event {
+        Event::key(code, KeyModifiers::NONE)
+    }
+
+    fn type_str(input: &mut TextInput, content: &str) {
+        for c in content.chars() {
+            input.handle_event(&key(KeyCode::Char(c)));
+        }
+    }
+
+    fn row(buffer: &Buffer) -> String {
+        (0..buffer.area().width)
+            .map(|x| buffer.get_cell(x, 0).unwrap().symbol.as_str())
+            .collect()
+    }
+
+    #[test]
+    fn test_typing_and_deleting() {
+        let changes = Rc::new(RefCell::new(Vec::new()));
+        let log = changes.clone();
+        let mut input = TextInput::new(0, 0, 10)
+            .on_change(move |value| log.borrow_mut().push(value.to_string()));
+
+        type_str(&mut input, "helo");
+        input.handle_event(&key(KeyCode::Left));
+        type_str(&mut input, "l");
+        assert_eq!(input.get_value(), "hello");
+        assert_eq!(input.cursor(), 4);
+
+        input.handle_event(&key(KeyCode::Delete));
+        input.handle_event(&key(KeyCode::Home));
+        input.handle_event(&key(KeyCode::Backspace));
+        input.handle_event(&key(KeyCode::Delete));
+        assert_eq!(input.get_value(), "ell");
+        assert_eq!(changes.borrow().len(), 7);
+        assert_eq!(changes.borrow().last().unwrap(), "ell");
+    }
+
+    #[test]
+    fn test_word_jumps() {
+        let mut input = TextInput::new(0, 0, 20).value("one two  three");
+        let ctrl = |code| Event::key(code, KeyModifiers::CTRL);
+
+        input.handle_event(&ctrl(KeyCode::Left));
+        assert_eq!(input.cursor(), 9);
+        input.handle_event(&ctrl(KeyCode::Left));
+        assert_eq!(input.cursor(), 4);
+        input.handle_event(&ctrl(KeyCode::Right));
+        assert_eq!(input.cursor(), 7);
+
+        input.handle_event(&Event::key(KeyCode::Backspace, KeyModifiers::ALT));
+        assert_eq!(input.get_value(), "one   three");
+        input.handle_event(&ctrl(KeyCode::Delete));
+        assert_eq!(input.get_value(), "one ");
+    }
+
+    #[test]
+    fn test_selection() {
+        let mut input = TextInput::new(0, 0, 20).value("hello world");
+        let shift = |code| Event::key(code, KeyModifiers::SHIFT | KeyModifiers::CTRL);
+
+        input.handle_event(&shift(KeyCode::Left));
+        assert_eq!(input.selected_text(), "world");
+        type_str(&mut input, "there");
+        assert_eq!(input.get_value(), "hello there");
+        assert_eq!(input.selection(), None);
+
+        input.handle_event(&Event::key(KeyCode::Char('a'), KeyModifiers::CTRL));
+        assert_eq!(input.selected_text(), "hello there");
+        input.handle_event(&key(KeyCode::Left));
+        assert_eq!(input.cursor(), 0);
+        assert_eq!(input.selection(), None);
+
+        input.handle_event(&Event::key(KeyCode::End, KeyModifiers::SHIFT));
+        input.handle_event(&key(KeyCode::Backspace));
+        assert_eq!(input.get_value(), "");
+    }
+
+    #[test]
+    fn test_max_length() {
+        let mut input = TextInput::new(0, 0, 10).max_length(5).value("abcdefg");
+        assert_eq!(input.get_value(), "abcde");
+        type_str(&mut input, "x");
+        assert_eq!(input.get_value(), "abcde");
+
+        input.handle_event(&key(KeyCode::Backspace));
+        input.handle_event(&Event::Paste("日本語".to_string()));
+        assert_eq!(input.get_value(), "abcd日");
+    }
+
+    #[test]
+    fn test_submit() {
+        let submitted = Rc::new(RefCell::new(String::new()));
+        let last = submitted.clone();
+        let mut input =
+            TextInput::new(0, 0, 10).on_submit(move |value| *last.borrow_mut() = value.to_string());
+        type_str(&mut input, "ok");
+        assert!(input.handle_event(&key(KeyCode::Enter)));
+        assert_eq!(*submitted.borrow(), "ok");
+        assert!(!input.handle_event(&key(KeyCode::Esc)));
+    }
+
+    #[test]
+    fn test_draw_placeholder_and_password() -> Result<(), Error> {
+        let mut input = TextInput::new(0, 0, 6)
+            .placeholder("Password")
+            .password(true);
+        let mut buffer = Buffer::new(6, 1);
+        input.draw(&mut buffer)?;
+        assert_eq!(row(&buffer), "Passwo");
+        assert!(buffer
+            .get_cell(0, 0)
+            .unwrap()
+            .style
+            .attributes
+            .contains(Attributes::DIM));
+
+        type_str(&mut input, "secret word");
+        input.handle_event(&Event::key(KeyCode::Left, KeyModifiers::CTRL));
+        assert_eq!(input.cursor(), 0);
+        buffer.clear();
+        input.draw(&mut buffer)?;
+        assert_eq!(row(&buffer), "••••••");
+        Ok(())
+    }
+
+    #[test]
+    fn test_horizontal_scrolling() -> Result<(), Error> {
+        let mut input = TextInput::new(1, 0, 4).value("abcdefgh");
+        input.set_focused(true);
+        let mut buffer = Buffer::new(6, 1);
+        input.draw(&mut buffer)?;
+        // The last column is kept free for the cursor.
+        assert_eq!(row(&buffer), " fgh  ");
+        assert_eq!(buffer.cursor(), Some((4, 0)));
+
+        input.handle_event(&key(KeyCode::Home));
+        buffer.clear();
+        input.draw(&mut buffer)?;
+        assert_eq!(row(&buffer), " abcd ");
+        assert_eq!(buffer.cursor(), Some((1, 0)));
+
+        input.handle_event(&Event::mouse(
+            MouseEventKind::Press,
+            MouseButton::Left,
+            2,
+            0,
+        ));
+        assert_eq!(input.cursor(), 2);
+        input.set_focused(false);
+        buffer.clear();
+        input.draw(&mut buffer)?;
+        assert_eq!(buffer.cursor(), None);
+        Ok(())
+    }
+}
@@ -0,0 +1,198 @@
+use crate::{buffer::str_width, Attributes, Buffer, Error, Event, KeyCode, Style, UIElement};
+
+/// An on/off switch drawn as `[ ON] label` or `[OFF] label`.
+///
+/// Enter, Space and a click flip it, Right and Left turn it on and off.
+pub struct Toggle {
+    x: usize,
+    y: usize,
+    label: String,
+    on: bool,
+    style: Style,
+    on_style: Style,
+    focus_style: Style,
+    focused: bool,
+    on_change: Option<Box<dyn FnMut(bool)>>,
+}
+
+impl Toggle {
+    pub fn new(x: usize, y: usize, label: &str) -> Self {
+        Self {
+            x,
+            y,
+            label: label.to_string(),
+            on: false,
+            style: Style::default(),
+            on_style: Style::new().attributes(Attributes::BOLD),
+            focus_style: Style::new().attributes(Attributes::REVERSE),
+            focused: false,
+            on_change: None,
+        }
+    }
+
+    pub fn on(mut self, on: bool) -> Self {
+        self.on = on;
+        self
+    }
+    pub fn style(mut self, style: Style) -> Self {
+        self.style = style;
+        self
+    }
+    /// The style of the switch while it is on.
+    pub fn on_style(mut self, style: Style) -> Self {
+        self.on_style = style;
+        self
+    }
+    /// The style of the switch while the toggle has the focus.
+    pub fn focus_style(mut self, style: Style) -> Self {
+        self.focus_style = style;
+        self
+    }
+    /// Called with the new state whenever the toggle is switched by an event.
+    pub fn on_change(mut self, callback: impl FnMut(bool) + 'static) -> Self {
+        self.on_change = Some(Box::new(callback));
+        self
+    }
+
+    pub fn is_on(&self) -> bool {
+        self.on
+    }
+    /// Changes the state without calling `on_change`.
+    pub fn set_on(&mut self, on: bool) {
+        self.on = on;
+    }
+
+    pub fn is_focused(&self) -> bool {
+        self.focused
+    }
+
+    /// Switches to `on`, calling `on_change` if that changes the state.
+    fn switch(&mut self, on: bool) {
+        if self.on == on {
+            return;
+        }
+        self.on = on;
+        if let Some(on_change) = self.on_change.as_mut() {
+            on_change(on);
+        }
+    }
+}
+
+impl UIElement for Toggle {
+    fn draw(&self, buffer: &mut Buffer) -> Result<(), Error> {
+        let (switch, mut style) = if self.on {
+            ("[ ON]", self.style.patch(self.on_style))
+        } else {
+            ("[OFF]", self.style)
+        };
+        if self.focused {
+            style = style.patch(self.focus_style);
+        }
+        buffer.draw_str(self.x, self.y, switch, style)?;
+        buffer.draw_str(self.x + 6, self.y, &self.label, self.style)?;
+        Ok(())
+    }
+    fn set_position(&mut self, x: usize, y: usize) {
+        self.x = x;
+        self.y = y;
+    }
+    fn get_position(&self) -> (usize, usize) {
+        (self.x, self.y)
+    }
+    fn preferred_size(&self) -> (usize, usize) {
+        (str_width(&self.label) + 6, 1)
+    }
+    fn is_focusable(&self) -> bool {
+        true
+    }
+    fn set_focused(&mut self, focused: bool) {
+        self.focused = focused;
+    }
+    fn handle_event(&mut self, event: &Event) -> bool {
+        if event.is_activation() || event.is_left_click() {
+            self.switch(!self.on);
+            return true;
+        }
+        match event {
+            Event::Key {
+                code: KeyCode::Right,
+                ..
+            } => self.switch(true),
+            Event::Key {
+                code: KeyCode::Left,
+                ..
+            } => self.switch(false),
+            _ => return false,
+        }
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{KeyModifiers, MouseButton, MouseEventKind};
+    use std::{cell::Cell, rc::Rc};
+
+    #[test]
+    fn test_switch() {
+        let changes = Rc::new(Cell::new(0));
+        let counter = changes.clone();
+        let mut toggle =
+            Toggle::new(0, 0, "Dark mode").on_change(move |_| counter.set(counter.get() + 1));
+        let key = |code| Event::key(code, KeyModifiers::NONE);
+
+        assert!(toggle.handle_event(&key(KeyCode::Right)));
+        assert!(toggle.is_on());
+        // Already on, nothing changes.
+        assert!(toggle.handle_event(&key(KeyCode::Right)));
+        assert_eq!(changes.get(), 1);
+
+        assert!(toggle.handle_event(&key(KeyCode::Enter)));
+        assert!(!toggle.is_on());
+        assert!(toggle.handle_event(&Event::mouse(
+            MouseEventKind::Press,
+            MouseButton::Left,
+            0,
+            0
+        )));
+        assert!(toggle.is_on());
+        assert!(toggle.handle_event(&key(KeyCode::Left)));
+        assert!(!toggle.is_on());
+        assert_eq!(changes.get(), 4);
+
+        assert!(!toggle.handle_event(&key(KeyCode::Up)));
+    }
+
+    #[test]
+    fn test_draw() -> Result<(), Error> {
+        let mut toggle = Toggle::new(0, 0, "Dark").on(true);
+        let mut buffer = Buffer::new(10, 1);
+        toggle.draw(&mut buffer)?;
+        assert_eq!(
+            buffer.get_content().iter().collect::<String>(),
+            "[ ON] Dark"
+        );
+        assert!(buffer
+            .get_cell(2, 0)
+            .unwrap()
+            .style
+            .attributes
+            .contains(Attributes::BOLD));
+
+        toggle.set_on(false);
+        toggle.set_focused(true);
+        toggle.draw(&mut buffer)?;
+        assert_eq!(
+            buffer.get_content().iter().collect::<String>(),
+            "[OFF] Dark"
+        );
+        assert!(buffer
+            .get_cell(2, 0)
+            .unwrap()
+            .style
+            .attributes
+            .contains(Attributes::REVERSE));
+        Ok(())
+    }
+}
@@ -5,6 +5,8 @@ use std::{
 
 use nix::errno;
 
+use crate::Rect;
+
 pub enum Error {
     Errno(errno::Errno),
     IoError(io::Error),
@@ -14,6 +16,23 @@ pub enum Error {
         width: usize,
         height: usize,
     },
+    /// Drawing outside of the current clip area in strict mode.
+    ClipError {
+        x: usize,
+        y: usize,
+        clip: Rect,
+    },
+    /// Adding or removing a sub element of an element that can't have any.
+    NotAContainer,
+    /// A scroll region that is empty or doesn't fit the terminal, `top` and
+    /// `bottom` are 1-based.
+    ScrollRegionError {
+        top: usize,
+        bottom: usize,
+        height: usize,
+    },
+    /// An element id containing [`crate::tree::PATH_SEPARATOR`].
+    InvalidId(String),
 }
 
 impl From<errno::Errno> for Error {
@@ -33,7 +52,11 @@ impl Display for Error {
         match *self {
             Error::IoError(ref err) => write!(f, "IO Error: {}", err),
             Error::PositionError{ x, y, width, height } => write!(f, "Position Error: 'x' must be less than the buffer's width ({}) and 'y' must be less than the buffer's height ({}). Given: x = {}, y = {}", width, height, x, y),
+            Error::ClipError { x, y, clip } => write!(f, "Clip Error: the position must be inside the clip area (x = {}, y = {}, width = {}, height = {}). Given: x = {}, y = {}", clip.x, clip.y, clip.width, clip.height, x, y),
             Error::Errno(ref err) => write!(f, "Errno: {}", err),
+            Error::NotAContainer => write!(f, "Not A Container: the element can't have sub elements"),
+            Error::ScrollRegionError { top, bottom, height } => write!(f, "Scroll Region Error: 'top' must be at least 1 and less than 'bottom', which must not be greater than the terminal's height ({}). Given: top = {}, bottom = {}", height, top, bottom),
+            Error::InvalidId(ref id) => write!(f, "Invalid Id: element ids must not contain '/'. Given: {:?}", id),
         }
     }
 }
@@ -55,6 +78,24 @@ impl Debug for Error {
                 .field("width", width)
                 .field("height", height)
                 .finish(),
+            Self::ClipError { x, y, clip } => f
+                .debug_struct("ClipError")
+                .field("x", x)
+                .field("y", y)
+                .field("clip", clip)
+                .finish(),
+            Self::NotAContainer => write!(f, "NotAContainer"),
+            Self::ScrollRegionError {
+                top,
+                bottom,
+                height,
+            } => f
+                .debug_struct("ScrollRegionError")
+                .field("top", top)
+                .field("bottom", bottom)
+                .field("height", height)
+                .finish(),
+            Self::InvalidId(id) => f.debug_tuple("InvalidId").field(id).finish(),
         }
     }
 }
@@ -108,6 +149,55 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_display_clip_error() {
+        let error = Error::ClipError {
+            x: 10,
+            y: 20,
+            clip: Rect::new(1, 2, 3, 4),
+        };
+        assert_eq!(
+            format!("{}", error),
+            "Clip Error: the position must be inside the clip area (x = 1, y = 2, width = 3, height = 4). Given: x = 10, y = 20"
+        );
+    }
+
+    #[test]
+    fn test_display_not_a_container() {
+        assert_eq!(
+            format!("{}", Error::NotAContainer),
+            "Not A Container: the element can't have sub elements"
+        );
+        assert_eq!(format!("{:?}", Error::NotAContainer), "NotAContainer");
+    }
+
+    #[test]
+    fn test_display_scroll_region_error() {
+        let error = Error::ScrollRegionError {
+            top: 5,
+            bottom: 3,
+            height: 10,
+        };
+        assert_eq!(
+            format!("{}", error),
+            "Scroll Region Error: 'top' must be at least 1 and less than 'bottom', which must not be greater than the terminal's height (10). Given: top = 5, bottom = 3"
+        );
+        assert_eq!(
+            format!("{:?}", error),
+            "ScrollRegionError { top: 5, bottom: 3, height: 10 }"
+        );
+    }
+
+    #[test]
+    fn test_display_invalid_id() {
+        let error = Error::InvalidId("a/b".to_string());
+        assert_eq!(
+            format!("{}", error),
+            "Invalid Id: element ids must not contain '/'. Given: \"a/b\""
+        );
+        assert_eq!(format!("{:?}", error), "InvalidId(\"a/b\")");
+    }
+
     #[test]
     fn test_display_errno() {
         let errno = errno::Errno::EACCES;
@@ -142,6 +232,19 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_debug_clip_error() {
+        let error = Error::ClipError {
+            x: 10,
+            y: 20,
+            clip: Rect::new(1, 2, 3, 4),
+        };
+        assert_eq!(
+            format!("{:?}", error),
+            "ClipError { x: 10, y: 20, clip: Rect { x: 1, y: 2, width: 3, height: 4 } }"
+        );
+    }
+
     #[test]
     fn test_debug_errno() {
         let errno = errno::Errno::EACCES;
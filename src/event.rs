@@ -0,0 +1,234 @@
+use std::ops::{BitOr, BitOrAssign};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Event {
+    Key {
+        code: KeyCode,
+        modifiers: KeyModifiers,
+    },
+    /// A mouse event, `x` and `y` are zero-based cell coordinates of the screen.
+    /// [`crate::Rectext::handle_event`] makes them relative to the element it
+    /// passes the event to, so they are negative to the left of or above it.
+    Mouse {
+        kind: MouseEventKind,
+        button: MouseButton,
+        x: isize,
+        y: isize,
+        modifiers: KeyModifiers,
+    },
+    /// The terminal was resized to (width, height).
+    Resize(usize, usize),
+    /// Text pasted while bracketed paste is enabled.
+    Paste(String),
+    /// The terminal window gained focus, reported while focus reporting is enabled.
+    FocusGained,
+    /// The terminal window lost focus, reported while focus reporting is enabled.
+    FocusLost,
+}
+
+impl Event {
+    pub fn key(code: KeyCode, modifiers: KeyModifiers) -> Self {
+        Event::Key { code, modifiers }
+    }
+
+    /// A mouse event without modifiers.
+    pub fn mouse(kind: MouseEventKind, button: MouseButton, x: isize, y: isize) -> Self {
+        Event::Mouse {
+            kind,
+            button,
+            x,
+            y,
+            modifiers: KeyModifiers::NONE,
+        }
+    }
+
+    /// Whether this is Enter or Space, the keys that activate buttons and similar widgets.
+    pub fn is_activation(&self) -> bool {
+        matches!(
+            self,
+            Event::Key {
+                code: KeyCode::Enter | KeyCode::Char(' '),
+                modifiers: KeyModifiers::NONE,
+            }
+        )
+    }
+
+    /// Whether this is a press of the left mouse button.
+    pub fn is_left_click(&self) -> bool {
+        matches!(
+            self,
+            Event::Mouse {
+                kind: MouseEventKind::Press,
+                button: MouseButton::Left,
+                ..
+            }
+        )
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum KeyCode {
+    Char(char),
+    Enter,
+    Tab,
+    /// Shift + Tab, which terminals report as its own sequence.
+    BackTab,
+    Backspace,
+    Esc,
+    Left,
+    Right,
+    Up,
+    Down,
+    Home,
+    End,
+    PageUp,
+    PageDown,
+    Insert,
+    Delete,
+    /// Function keys, F1 to F12.
+    F(u8),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MouseEventKind {
+    Press,
+    Release,
+    /// The mouse moved while a button was held down.
+    Drag,
+    /// The mouse moved without any button held down, only reported in
+    /// [`MouseMode::AnyEvent`].
+    Moved,
+    ScrollUp,
+    ScrollDown,
+    ScrollLeft,
+    ScrollRight,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MouseButton {
+    Left,
+    Middle,
+    Right,
+    /// No button is involved, e.g. for [`MouseEventKind::Moved`] or when the
+    /// terminal does not report which button was released.
+    None,
+}
+
+/// The xterm mouse tracking modes, each one reports a superset of the events of the previous one.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MouseMode {
+    /// Button presses only (mode 9).
+    X10,
+    /// Button presses and releases, including the wheel (mode 1000).
+    Normal,
+    /// Like [`MouseMode::Normal`], plus motion while a button is held down (mode 1002).
+    ButtonEvent,
+    /// Like [`MouseMode::ButtonEvent`], plus all motion (mode 1003).
+    AnyEvent,
+}
+
+impl MouseMode {
+    pub(crate) fn code(&self) -> u16 {
+        match self {
+            MouseMode::X10 => 9,
+            MouseMode::Normal => 1000,
+            MouseMode::ButtonEvent => 1002,
+            MouseMode::AnyEvent => 1003,
+        }
+    }
+}
+
+/// A set of modifier keys held while a key was pressed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct KeyModifiers(u8);
+
+impl KeyModifiers {
+    pub const NONE: KeyModifiers = KeyModifiers(0);
+    pub const SHIFT: KeyModifiers = KeyModifiers(1 << 0);
+    pub const ALT: KeyModifiers = KeyModifiers(1 << 1);
+    pub const CTRL: KeyModifiers = KeyModifiers(1 << 2);
+
+    pub fn contains(&self, other: KeyModifiers) -> bool {
+        self.0 & other.0 == other.0
+    }
+
+    pub fn insert(&mut self, other: KeyModifiers) {
+        self.0 |= other.0;
+    }
+
+    pub fn remove(&mut self, other: KeyModifiers) {
+        self.0 &= !other.0;
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0 == 0
+    }
+
+    /// Decodes the modifier parameter of xterm style sequences, e.g. the `5` in `ESC [ 1 ; 5 A`.
+    pub(crate) fn from_xterm_param(param: u16) -> Self {
+        let bits = param.saturating_sub(1);
+        let mut modifiers = KeyModifiers::NONE;
+        if bits & 1 != 0 {
+            modifiers.insert(KeyModifiers::SHIFT);
+        }
+        if bits & 2 != 0 {
+            modifiers.insert(KeyModifiers::ALT);
+        }
+        if bits & 4 != 0 {
+            modifiers.insert(KeyModifiers::CTRL);
+        }
+        modifiers
+    }
+}
+
+impl BitOr for KeyModifiers {
+    type Output = Self;
+
+    fn bitor(self, rhs: Self) -> Self::Output {
+        KeyModifiers(self.0 | rhs.0)
+    }
+}
+
+impl BitOrAssign for KeyModifiers {
+    fn bitor_assign(&mut self, rhs: Self) {
+        self.0 |= rhs.0;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_activation() {
+        assert!(Event::key(KeyCode::Enter, KeyModifiers::NONE).is_activation());
+        assert!(Event::key(KeyCode::Char(' '), KeyModifiers::NONE).is_activation());
+        assert!(!Event::key(KeyCode::Enter, KeyModifiers::ALT).is_activation());
+        assert!(!Event::key(KeyCode::Char('x'), KeyModifiers::NONE).is_activation());
+        assert!(Event::mouse(MouseEventKind::Press, MouseButton::Left, 0, 0).is_left_click());
+        assert!(!Event::mouse(MouseEventKind::Release, MouseButton::Left, 0, 0).is_left_click());
+    }
+
+    #[test]
+    fn test_modifiers() {
+        let mut modifiers = KeyModifiers::CTRL | KeyModifiers::ALT;
+        assert!(modifiers.contains(KeyModifiers::CTRL));
+        assert!(modifiers.contains(KeyModifiers::ALT));
+        assert!(!modifiers.contains(KeyModifiers::SHIFT));
+
+        modifiers.remove(KeyModifiers::CTRL | KeyModifiers::ALT);
+        assert!(modifiers.is_empty());
+    }
+
+    #[test]
+    fn test_modifiers_from_xterm_param() {
+        assert_eq!(KeyModifiers::from_xterm_param(1), KeyModifiers::NONE);
+        assert_eq!(KeyModifiers::from_xterm_param(2), KeyModifiers::SHIFT);
+        assert_eq!(KeyModifiers::from_xterm_param(3), KeyModifiers::ALT);
+        assert_eq!(KeyModifiers::from_xterm_param(5), KeyModifiers::CTRL);
+        assert_eq!(
+            KeyModifiers::from_xterm_param(8),
+            KeyModifiers::SHIFT | KeyModifiers::ALT | KeyModifiers::CTRL
+        );
+    }
+}
@@ -0,0 +1,200 @@
+use std::{
+    io::{self, Read, Write},
+    ops::{Deref, DerefMut},
+    os::fd::{AsRawFd, RawFd},
+};
+
+use crate::{Error, Event, KeyCode, KeyModifiers, MouseButton, MouseEventKind, Rectext};
+
+/// Input for a [`Harness`] that never has anything to read.
+pub struct NoInput;
+
+impl Read for NoInput {
+    fn read(&mut self, _buf: &mut [u8]) -> io::Result<usize> {
+        Ok(0)
+    }
+}
+
+impl AsRawFd for NoInput {
+    fn as_raw_fd(&self) -> RawFd {
+        -1
+    }
+}
+
+/// Output for a [`Harness`] that discards everything written to it.
+pub struct NoOutput;
+
+impl Write for NoOutput {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        Ok(buf.len())
+    }
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+impl AsRawFd for NoOutput {
+    fn as_raw_fd(&self) -> RawFd {
+        -1
+    }
+}
+
+/// Runs a [`Rectext`] without a terminal, to test user interfaces by sending
+/// them synthetic events and checking what they draw.
+///
+/// Derefs to the [`Rectext`], so elements are added and drawn as usual.
+pub struct Harness {
+    rectext: Rectext<NoInput, NoOutput>,
+}
+
+impl Harness {
+    pub fn new(width: usize, height: usize) -> Self {
+        Self {
+            rectext: Rectext::new(width, height, NoInput, NoOutput),
+        }
+    }
+
+    /// Handles `event` like it was read from the terminal, returns whether it was used.
+    pub fn send(&mut self, event: Event) -> bool {
+        self.rectext.handle_event(&event)
+    }
+
+    /// Presses a key without modifiers.
+    pub fn press(&mut self, code: KeyCode) -> bool {
+        self.send(Event::key(code, KeyModifiers::NONE))
+    }
+
+    /// Types `content` one key at a time, newlines are sent as Enter.
+    pub fn type_str(&mut self, content: &str) {
+        for c in content.chars() {
+            match c {
+                '\n' => self.press(KeyCode::Enter),
+                c => self.press(KeyCode::Char(c)),
+            };
+        }
+    }
+
+    /// Presses and releases the left mouse button at (x, y), returns whether the press was used.
+    pub fn click(&mut self, x: isize, y: isize) -> bool {
+        let used = self.send(Event::mouse(MouseEventKind::Press, MouseButton::Left, x, y));
+        self.send(Event::mouse(
+            MouseEventKind::Release,
+            MouseButton::Left,
+            x,
+            y,
+        ));
+        used
+    }
+
+    /// Moves the mouse pointer to (x, y) without any button held down.
+    pub fn move_mouse(&mut self, x: isize, y: isize) -> bool {
+        self.send(Event::mouse(MouseEventKind::Moved, MouseButton::None, x, y))
+    }
+
+    /// Drags with the left mouse button from `from` to `to`, both (x, y).
+    /// Returns whether the press was used.
+    pub fn drag(&mut self, from: (isize, isize), to: (isize, isize)) -> bool {
+        let used = self.send(Event::mouse(
+            MouseEventKind::Press,
+            MouseButton::Left,
+            from.0,
+            from.1,
+        ));
+        self.send(Event::mouse(
+            MouseEventKind::Drag,
+            MouseButton::Left,
+            to.0,
+            to.1,
+        ));
+        self.send(Event::mouse(
+            MouseEventKind::Release,
+            MouseButton::Left,
+            to.0,
+            to.1,
+        ));
+        used
+    }
+
+    /// Draws and returns the screen, one string per row.
+    pub fn render(&mut self) -> Result<Vec<String>, Error> {
+        self.rectext.draw()?;
+        let (_, height) = self.rectext.size();
+        Ok((0..height).map(|y| self.row(y)).collect())
+    }
+
+    /// The text of a row of the screen as of the last draw.
+    pub fn row(&self, y: usize) -> String {
+        let screen = self.rectext.screen();
+        (0..screen.area().width)
+            .filter_map(|x| screen.get_cell(x, y))
+            .map(|cell| cell.symbol.as_str())
+            .collect()
+    }
+}
+
+impl Deref for Harness {
+    type Target = Rectext<NoInput, NoOutput>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.rectext
+    }
+}
+
+impl DerefMut for Harness {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.rectext
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Button, Container, TextInput, UIElement};
+    use std::{cell::Cell, cell::RefCell, rc::Rc};
+
+    #[test]
+    fn test_fill_in_a_form() -> Result<(), Error> {
+        let mut harness = Harness::new(12, 3);
+        let submitted = Rc::new(Cell::new(false));
+        let clicked = submitted.clone();
+
+        let mut form = Container::new(1, 0, 11, 3);
+        let name = Rc::new(RefCell::new(TextInput::new(0, 0, 10)));
+        form.add_sub_element("name".to_string(), name.clone())?;
+        let ok = Button::new(0, 2, "OK").on_click(move || clicked.set(true));
+        form.add_sub_element("ok".to_string(), Rc::new(RefCell::new(ok)))?;
+        harness.add_element("form".to_string(), Rc::new(RefCell::new(form)))?;
+
+        assert!(harness.press(KeyCode::Tab));
+        harness.type_str("Ada");
+        assert_eq!(harness.focused(), Some("form/name"));
+        assert_eq!(name.borrow().get_value(), "Ada");
+
+        assert!(harness.click(2, 2));
+        assert!(submitted.get());
+        assert_eq!(harness.focused(), Some("form/ok"));
+
+        let screen = harness.render()?;
+        assert_eq!(screen, vec![" Ada        ", "            ", " [ OK ]     "]);
+        Ok(())
+    }
+
+    #[test]
+    fn test_dragging_off_a_button_cancels_the_click() {
+        let mut harness = Harness::new(10, 3);
+        let clicks = Rc::new(Cell::new(0));
+        let counter = clicks.clone();
+        let ok = Button::new(2, 1, "OK").on_click(move || counter.set(counter.get() + 1));
+        harness
+            .add_element("ok".to_string(), Rc::new(RefCell::new(ok)))
+            .unwrap();
+
+        assert!(harness.drag((3, 1), (8, 1)));
+        assert!(harness.drag((3, 1), (0, 1)));
+        assert!(harness.drag((3, 1), (3, 0)));
+        assert_eq!(clicks.get(), 0);
+
+        assert!(harness.drag((3, 1), (7, 1)));
+        assert_eq!(clicks.get(), 1);
+    }
+}
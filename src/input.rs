@@ -0,0 +1,665 @@
+use crate::event::{Event, KeyCode, KeyModifiers, MouseButton, MouseEventKind};
+
+const ESC: u8 = 0x1b;
+const PASTE_START: &[u8] = b"\x1b[200~";
+
+enum Parsed {
+    Event(Event, usize),
+    Incomplete,
+    /// The given number of bytes could not be decoded and should be skipped.
+    Invalid(usize),
+}
+
+/// Decodes raw bytes read from the terminal into [`Event`]s.
+///
+/// Bytes can be pushed in arbitrary chunks, a sequence that is split across
+/// multiple reads is kept until the rest of it arrives.
+#[derive(Default)]
+pub struct InputParser {
+    pending: Vec<u8>,
+}
+
+impl InputParser {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn push(&mut self, bytes: &[u8]) {
+        self.pending.extend_from_slice(bytes);
+    }
+
+    /// Returns true if there are bytes that could not be decoded yet.
+    pub fn has_pending(&self) -> bool {
+        !self.pending.is_empty()
+    }
+
+    /// Returns the next complete event, if any.
+    pub fn next_event(&mut self) -> Option<Event> {
+        while !self.pending.is_empty() {
+            match parse(&self.pending) {
+                Parsed::Event(event, consumed) => {
+                    self.pending.drain(..consumed);
+                    return Some(event);
+                }
+                Parsed::Incomplete => return None,
+                Parsed::Invalid(consumed) => {
+                    self.pending.drain(..consumed);
+                }
+            }
+        }
+        None
+    }
+
+    /// Resolves an incomplete sequence once no more bytes are expected for it,
+    /// e.g. after the escape timeout. A lone `ESC` becomes [`KeyCode::Esc`] and
+    /// a paste that never ended becomes an [`Event::Paste`] of the text so far.
+    pub fn flush(&mut self) -> Option<Event> {
+        if let Some(content) = self.pending.strip_prefix(PASTE_START) {
+            let event = Event::Paste(String::from_utf8_lossy(content).into_owned());
+            self.pending.clear();
+            return Some(event);
+        }
+        match self.pending.as_slice() {
+            [] => None,
+            [ESC, c @ (b'[' | b'O')] => {
+                let event = Event::key(KeyCode::Char(*c as char), KeyModifiers::ALT);
+                self.pending.clear();
+                Some(event)
+            }
+            [ESC, ..] => {
+                self.pending.remove(0);
+                Some(Event::key(KeyCode::Esc, KeyModifiers::NONE))
+            }
+            _ => {
+                // An incomplete UTF-8 sequence that will never be completed.
+                self.pending.clear();
+                None
+            }
+        }
+    }
+}
+
+fn parse(bytes: &[u8]) -> Parsed {
+    if bytes[0] == ESC {
+        parse_escape(bytes)
+    } else {
+        parse_plain(bytes)
+    }
+}
+
+fn parse_escape(bytes: &[u8]) -> Parsed {
+    match bytes.get(1) {
+        None => Parsed::Incomplete,
+        Some(b'[') => parse_csi(bytes),
+        Some(b'O') => parse_ss3(bytes),
+        Some(&ESC) => Parsed::Event(Event::key(KeyCode::Esc, KeyModifiers::NONE), 1),
+        // Alt + key is sent as ESC followed by the key.
+        Some(_) => match parse_plain(&bytes[1..]) {
+            Parsed::Event(Event::Key { code, modifiers }, consumed) => Parsed::Event(
+                Event::key(code, modifiers | KeyModifiers::ALT),
+                consumed + 1,
+            ),
+            Parsed::Invalid(consumed) => Parsed::Invalid(consumed + 1),
+            other => other,
+        },
+    }
+}
+
+/// Parses `ESC [ <params> <final>`.
+fn parse_csi(bytes: &[u8]) -> Parsed {
+    if bytes.get(2) == Some(&b'M') {
+        return parse_x10_mouse(bytes);
+    }
+
+    // Parameter and intermediate bytes are followed by the final byte.
+    let Some(final_index) = bytes[2..]
+        .iter()
+        .position(|b| !(0x20..=0x3f).contains(b))
+        .map(|i| i + 2)
+    else {
+        return Parsed::Incomplete;
+    };
+    if !(0x40..=0x7e).contains(&bytes[final_index]) {
+        // Only the broken sequence is skipped, the byte that ended it may
+        // start the next one, e.g. an `ESC`.
+        return Parsed::Invalid(final_index);
+    }
+
+    let consumed = final_index + 1;
+    if bytes[2] == b'<' {
+        return parse_sgr_mouse(&bytes[3..final_index], bytes[final_index], consumed);
+    }
+
+    let params = parse_params(&bytes[2..final_index]);
+    if bytes[final_index] == b'~' && params.first() == Some(&200) {
+        return parse_paste(bytes, consumed);
+    }
+
+    let modifiers = params
+        .get(1)
+        .map(|m| KeyModifiers::from_xterm_param(*m))
+        .unwrap_or_default();
+
+    let code = match bytes[final_index] {
+        b'A' => KeyCode::Up,
+        b'B' => KeyCode::Down,
+        b'C' => KeyCode::Right,
+        b'D' => KeyCode::Left,
+        b'H' => KeyCode::Home,
+        b'F' => KeyCode::End,
+        b'P' => KeyCode::F(1),
+        b'Q' => KeyCode::F(2),
+        b'R' => KeyCode::F(3),
+        b'S' => KeyCode::F(4),
+        b'I' => return Parsed::Event(Event::FocusGained, consumed),
+        b'O' => return Parsed::Event(Event::FocusLost, consumed),
+        b'Z' => return Parsed::Event(Event::key(KeyCode::BackTab, KeyModifiers::SHIFT), consumed),
+        b'~' => match params.first() {
+            Some(1 | 7) => KeyCode::Home,
+            Some(2) => KeyCode::Insert,
+            Some(3) => KeyCode::Delete,
+            Some(4 | 8) => KeyCode::End,
+            Some(5) => KeyCode::PageUp,
+            Some(6) => KeyCode::PageDown,
+            Some(n @ 11..=15) => KeyCode::F((n - 10) as u8),
+            Some(n @ 17..=21) => KeyCode::F((n - 11) as u8),
+            Some(n @ 23..=24) => KeyCode::F((n - 12) as u8),
+            _ => return Parsed::Invalid(consumed),
+        },
+        _ => return Parsed::Invalid(consumed),
+    };
+
+    Parsed::Event(Event::key(code, modifiers), consumed)
+}
+
+/// Parses `ESC O <final>`, sent for F1-F4 and by some terminals for arrow keys.
+fn parse_ss3(bytes: &[u8]) -> Parsed {
+    let Some(c) = bytes.get(2) else {
+        return Parsed::Incomplete;
+    };
+    let code = match c {
+        b'A' => KeyCode::Up,
+        b'B' => KeyCode::Down,
+        b'C' => KeyCode::Right,
+        b'D' => KeyCode::Left,
+        b'H' => KeyCode::Home,
+        b'F' => KeyCode::End,
+        b'P' => KeyCode::F(1),
+        b'Q' => KeyCode::F(2),
+        b'R' => KeyCode::F(3),
+        b'S' => KeyCode::F(4),
+        _ => return Parsed::Invalid(3),
+    };
+    Parsed::Event(Event::key(code, KeyModifiers::NONE), 3)
+}
+
+/// Parses a bracketed paste, the text between `ESC [ 200 ~` and `ESC [ 201 ~`.
+fn parse_paste(bytes: &[u8], start: usize) -> Parsed {
+    const END: &[u8] = b"\x1b[201~";
+
+    match bytes[start..].windows(END.len()).position(|w| w == END) {
+        Some(len) => {
+            let content = String::from_utf8_lossy(&bytes[start..start + len]).into_owned();
+            Parsed::Event(Event::Paste(content), start + len + END.len())
+        }
+        None => Parsed::Incomplete,
+    }
+}
+
+/// Parses `ESC [ < <button> ; <x> ; <y> (M|m)`, the SGR (1006) extended mouse encoding.
+fn parse_sgr_mouse(params: &[u8], final_byte: u8, consumed: usize) -> Parsed {
+    let params = parse_params(params);
+    let (Some(cb), Some(cx), Some(cy)) = (params.first(), params.get(1), params.get(2)) else {
+        return Parsed::Invalid(consumed);
+    };
+    let released = final_byte == b'm';
+    if final_byte != b'M' && !released {
+        return Parsed::Invalid(consumed);
+    }
+
+    match mouse_event(
+        *cb,
+        (*cx as usize).saturating_sub(1),
+        (*cy as usize).saturating_sub(1),
+        released,
+    ) {
+        Some(event) => Parsed::Event(event, consumed),
+        None => Parsed::Invalid(consumed),
+    }
+}
+
+/// Parses `ESC [ M <button> <x> <y>`, the legacy encoding where each value is a
+/// single byte offset by 32.
+fn parse_x10_mouse(bytes: &[u8]) -> Parsed {
+    if bytes.len() < 6 {
+        return Parsed::Incomplete;
+    }
+    let cb = bytes[3].saturating_sub(32) as u16;
+    let x = bytes[4].saturating_sub(33) as usize;
+    let y = bytes[5].saturating_sub(33) as usize;
+
+    match mouse_event(cb, x, y, false) {
+        Some(event) => Parsed::Event(event, 6),
+        None => Parsed::Invalid(6),
+    }
+}
+
+/// Decodes the button byte shared by the SGR and the legacy mouse encodings.
+fn mouse_event(cb: u16, x: usize, y: usize, released: bool) -> Option<Event> {
+    // Extra buttons (8-11) are not supported.
+    if cb >= 128 {
+        return None;
+    }
+
+    let mut modifiers = KeyModifiers::NONE;
+    if cb & 4 != 0 {
+        modifiers.insert(KeyModifiers::SHIFT);
+    }
+    if cb & 8 != 0 {
+        modifiers.insert(KeyModifiers::ALT);
+    }
+    if cb & 16 != 0 {
+        modifiers.insert(KeyModifiers::CTRL);
+    }
+
+    let button = match cb & 3 {
+        0 => MouseButton::Left,
+        1 => MouseButton::Middle,
+        2 => MouseButton::Right,
+        _ => MouseButton::None,
+    };
+
+    let (kind, button) = if cb & 64 != 0 {
+        let kind = match cb & 3 {
+            0 => MouseEventKind::ScrollUp,
+            1 => MouseEventKind::ScrollDown,
+            2 => MouseEventKind::ScrollLeft,
+            _ => MouseEventKind::ScrollRight,
+        };
+        (kind, MouseButton::None)
+    } else if cb & 32 != 0 {
+        match button {
+            MouseButton::None => (MouseEventKind::Moved, button),
+            _ => (MouseEventKind::Drag, button),
+        }
+    } else if released || button == MouseButton::None {
+        (MouseEventKind::Release, button)
+    } else {
+        (MouseEventKind::Press, button)
+    };
+
+    Some(Event::Mouse {
+        kind,
+        button,
+        x: x as isize,
+        y: y as isize,
+        modifiers,
+    })
+}
+
+fn parse_params(bytes: &[u8]) -> Vec<u16> {
+    bytes
+        .split(|b| *b == b';')
+        .map(|param| {
+            std::str::from_utf8(param)
+                .ok()
+                .and_then(|p| p.parse().ok())
+                .unwrap_or(1)
+        })
+        .collect()
+}
+
+fn parse_plain(bytes: &[u8]) -> Parsed {
+    let key = |code| Parsed::Event(Event::key(code, KeyModifiers::NONE), 1);
+    let ctrl = |c| Parsed::Event(Event::key(KeyCode::Char(c), KeyModifiers::CTRL), 1);
+
+    match bytes[0] {
+        b'\r' | b'\n' => key(KeyCode::Enter),
+        b'\t' => key(KeyCode::Tab),
+        0x7f | 0x08 => key(KeyCode::Backspace),
+        ESC => key(KeyCode::Esc),
+        0x00 => ctrl(' '),
+        b @ 0x01..=0x1a => ctrl((b - 0x01 + b'a') as char),
+        b @ 0x1c..=0x1f => ctrl((b - 0x1c + b'4') as char),
+        b @ 0x20..=0x7e => key(KeyCode::Char(b as char)),
+        b => {
+            let len = match b {
+                0xc0..=0xdf => 2,
+                0xe0..=0xef => 3,
+                0xf0..=0xf7 => 4,
+                _ => return Parsed::Invalid(1),
+            };
+            if bytes.len() < len {
+                return Parsed::Incomplete;
+            }
+            match std::str::from_utf8(&bytes[..len]) {
+                Ok(s) => match s.chars().next() {
+                    Some(c) => Parsed::Event(Event::key(KeyCode::Char(c), KeyModifiers::NONE), len),
+                    None => Parsed::Invalid(len),
+                },
+                Err(_) => Parsed::Invalid(1),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse_all(bytes: &[u8]) -> Vec<Event> {
+        let mut parser = InputParser::new();
+        parser.push(bytes);
+        let mut events = Vec::new();
+        while let Some(event) = parser.next_event() {
+            events.push(event);
+        }
+        events
+    }
+
+    fn key(code: KeyCode) -> Event {
+        Event::key(code, KeyModifiers::NONE)
+    }
+
+    #[test]
+    fn test_plain_characters() {
+        assert_eq!(
+            parse_all(b"aZ1 "),
+            vec![
+                key(KeyCode::Char('a')),
+                key(KeyCode::Char('Z')),
+                key(KeyCode::Char('1')),
+                key(KeyCode::Char(' ')),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_utf8_characters() {
+        assert_eq!(
+            parse_all("é€😀".as_bytes()),
+            vec![
+                key(KeyCode::Char('é')),
+                key(KeyCode::Char('€')),
+                key(KeyCode::Char('😀')),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_control_keys() {
+        assert_eq!(
+            parse_all(b"\r\t\x7f\x03"),
+            vec![
+                key(KeyCode::Enter),
+                key(KeyCode::Tab),
+                key(KeyCode::Backspace),
+                Event::key(KeyCode::Char('c'), KeyModifiers::CTRL),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_arrow_keys() {
+        assert_eq!(
+            parse_all(b"\x1b[A\x1b[B\x1b[C\x1b[D\x1bOA"),
+            vec![
+                key(KeyCode::Up),
+                key(KeyCode::Down),
+                key(KeyCode::Right),
+                key(KeyCode::Left),
+                key(KeyCode::Up),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_navigation_keys() {
+        assert_eq!(
+            parse_all(b"\x1b[H\x1b[F\x1b[1~\x1b[4~\x1b[5~\x1b[6~\x1b[2~\x1b[3~\x1b[Z"),
+            vec![
+                key(KeyCode::Home),
+                key(KeyCode::End),
+                key(KeyCode::Home),
+                key(KeyCode::End),
+                key(KeyCode::PageUp),
+                key(KeyCode::PageDown),
+                key(KeyCode::Insert),
+                key(KeyCode::Delete),
+                Event::key(KeyCode::BackTab, KeyModifiers::SHIFT),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_function_keys() {
+        let events = parse_all(
+            b"\x1bOP\x1bOQ\x1bOR\x1bOS\x1b[15~\x1b[17~\x1b[18~\x1b[19~\x1b[20~\x1b[21~\x1b[23~\x1b[24~",
+        );
+        let expected: Vec<Event> = (1..=12).map(|n| key(KeyCode::F(n))).collect();
+        assert_eq!(events, expected);
+    }
+
+    #[test]
+    fn test_modified_keys() {
+        assert_eq!(
+            parse_all(b"\x1b[1;5C\x1b[1;2A\x1b[3;3~\x1b[1;5P"),
+            vec![
+                Event::key(KeyCode::Right, KeyModifiers::CTRL),
+                Event::key(KeyCode::Up, KeyModifiers::SHIFT),
+                Event::key(KeyCode::Delete, KeyModifiers::ALT),
+                Event::key(KeyCode::F(1), KeyModifiers::CTRL),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_alt_keys() {
+        assert_eq!(
+            parse_all(b"\x1bx\x1b\x01"),
+            vec![
+                Event::key(KeyCode::Char('x'), KeyModifiers::ALT),
+                Event::key(KeyCode::Char('a'), KeyModifiers::CTRL | KeyModifiers::ALT),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_partial_sequence() {
+        let mut parser = InputParser::new();
+        parser.push(b"\x1b[1;");
+        assert_eq!(parser.next_event(), None);
+        assert!(parser.has_pending());
+
+        parser.push(b"5A");
+        assert_eq!(
+            parser.next_event(),
+            Some(Event::key(KeyCode::Up, KeyModifiers::CTRL))
+        );
+        assert!(!parser.has_pending());
+    }
+
+    #[test]
+    fn test_partial_utf8() {
+        let mut parser = InputParser::new();
+        let bytes = "€".as_bytes();
+        parser.push(&bytes[..1]);
+        assert_eq!(parser.next_event(), None);
+        parser.push(&bytes[1..]);
+        assert_eq!(parser.next_event(), Some(key(KeyCode::Char('€'))));
+    }
+
+    #[test]
+    fn test_lone_escape() {
+        let mut parser = InputParser::new();
+        parser.push(b"\x1b");
+        assert_eq!(parser.next_event(), None);
+        assert_eq!(parser.flush(), Some(key(KeyCode::Esc)));
+        assert!(!parser.has_pending());
+    }
+
+    #[test]
+    fn test_flush_alt_bracket() {
+        let mut parser = InputParser::new();
+        parser.push(b"\x1b[");
+        assert_eq!(parser.next_event(), None);
+        assert_eq!(
+            parser.flush(),
+            Some(Event::key(KeyCode::Char('['), KeyModifiers::ALT))
+        );
+    }
+
+    #[test]
+    fn test_double_escape() {
+        assert_eq!(
+            parse_all(b"\x1b\x1b[A"),
+            vec![key(KeyCode::Esc), key(KeyCode::Up)]
+        );
+    }
+
+    fn mouse(kind: MouseEventKind, button: MouseButton, x: isize, y: isize) -> Event {
+        Event::Mouse {
+            kind,
+            button,
+            x,
+            y,
+            modifiers: KeyModifiers::NONE,
+        }
+    }
+
+    #[test]
+    fn test_sgr_mouse_click() {
+        assert_eq!(
+            parse_all(b"\x1b[<0;10;5M\x1b[<0;10;5m"),
+            vec![
+                mouse(MouseEventKind::Press, MouseButton::Left, 9, 4),
+                mouse(MouseEventKind::Release, MouseButton::Left, 9, 4),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_sgr_mouse_buttons_and_modifiers() {
+        assert_eq!(
+            parse_all(b"\x1b[<1;1;1M\x1b[<18;3;4M"),
+            vec![
+                mouse(MouseEventKind::Press, MouseButton::Middle, 0, 0),
+                Event::Mouse {
+                    kind: MouseEventKind::Press,
+                    button: MouseButton::Right,
+                    x: 2,
+                    y: 3,
+                    modifiers: KeyModifiers::CTRL,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_sgr_mouse_drag_and_move() {
+        assert_eq!(
+            parse_all(b"\x1b[<32;5;5M\x1b[<35;6;5M"),
+            vec![
+                mouse(MouseEventKind::Drag, MouseButton::Left, 4, 4),
+                mouse(MouseEventKind::Moved, MouseButton::None, 5, 4),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_sgr_mouse_wheel() {
+        assert_eq!(
+            parse_all(b"\x1b[<64;2;2M\x1b[<65;2;2M"),
+            vec![
+                mouse(MouseEventKind::ScrollUp, MouseButton::None, 1, 1),
+                mouse(MouseEventKind::ScrollDown, MouseButton::None, 1, 1),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_sgr_mouse_large_coordinates() {
+        assert_eq!(
+            parse_all(b"\x1b[<0;300;250M"),
+            vec![mouse(MouseEventKind::Press, MouseButton::Left, 299, 249)]
+        );
+    }
+
+    #[test]
+    fn test_sgr_mouse_partial() {
+        let mut parser = InputParser::new();
+        parser.push(b"\x1b[<0;10");
+        assert_eq!(parser.next_event(), None);
+        parser.push(b";5M");
+        assert_eq!(
+            parser.next_event(),
+            Some(mouse(MouseEventKind::Press, MouseButton::Left, 9, 4))
+        );
+    }
+
+    #[test]
+    fn test_x10_mouse() {
+        assert_eq!(
+            parse_all(b"\x1b[M !!\x1b[M#!!"),
+            vec![
+                mouse(MouseEventKind::Press, MouseButton::Left, 0, 0),
+                mouse(MouseEventKind::Release, MouseButton::None, 0, 0),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_bracketed_paste() {
+        assert_eq!(
+            parse_all(b"\x1b[200~hello\x1b[A world\x1b[201~x"),
+            vec![
+                Event::Paste("hello\x1b[A world".to_string()),
+                key(KeyCode::Char('x')),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_partial_paste() {
+        let mut parser = InputParser::new();
+        parser.push(b"\x1b[200~abc");
+        assert_eq!(parser.next_event(), None);
+        parser.push(b"def\x1b[20");
+        assert_eq!(parser.next_event(), None);
+        parser.push(b"1~");
+        assert_eq!(
+            parser.next_event(),
+            Some(Event::Paste("abcdef".to_string()))
+        );
+    }
+
+    #[test]
+    fn test_flush_unterminated_paste() {
+        let mut parser = InputParser::new();
+        parser.push(b"\x1b[200~abc\x1b[20");
+        assert_eq!(parser.next_event(), None);
+        assert_eq!(parser.flush(), Some(Event::Paste("abc\x1b[20".to_string())));
+        assert!(!parser.has_pending());
+    }
+
+    #[test]
+    fn test_focus() {
+        assert_eq!(
+            parse_all(b"\x1b[I\x1b[O"),
+            vec![Event::FocusGained, Event::FocusLost]
+        );
+    }
+
+    #[test]
+    fn test_unknown_sequence_is_skipped() {
+        assert_eq!(parse_all(b"\x1b[99~a"), vec![key(KeyCode::Char('a'))]);
+    }
+
+    #[test]
+    fn test_broken_sequence_keeps_following_input() {
+        assert_eq!(
+            parse_all(b"\x1b[1\x1b[Ab"),
+            vec![key(KeyCode::Up), key(KeyCode::Char('b'))]
+        );
+    }
+}
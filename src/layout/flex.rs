@@ -0,0 +1,433 @@
+use crate::{Direction, Rect};
+
+/// How items are distributed along the main axis when there is free space.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum JustifyContent {
+    #[default]
+    Start,
+    End,
+    Center,
+    /// The first item is at the start, the last one at the end.
+    SpaceBetween,
+    /// Every item has the same space on both of its sides.
+    SpaceAround,
+    /// The space between items and the edges is the same everywhere.
+    SpaceEvenly,
+}
+
+/// How items are placed along the cross axis within their line.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum AlignItems {
+    #[default]
+    Start,
+    End,
+    Center,
+    /// Items are resized to fill their line.
+    Stretch,
+}
+
+/// Space between the edges of a container and its content.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Padding {
+    pub top: usize,
+    pub right: usize,
+    pub bottom: usize,
+    pub left: usize,
+}
+
+impl Padding {
+    pub fn new(top: usize, right: usize, bottom: usize, left: usize) -> Self {
+        Self {
+            top,
+            right,
+            bottom,
+            left,
+        }
+    }
+
+    pub fn uniform(padding: usize) -> Self {
+        Self::new(padding, padding, padding, padding)
+    }
+
+    /// `vertical` for the top and bottom, `horizontal` for the left and right.
+    pub fn symmetric(vertical: usize, horizontal: usize) -> Self {
+        Self::new(vertical, horizontal, vertical, horizontal)
+    }
+
+    /// The area left inside `area` once the padding is removed.
+    pub fn shrink(&self, area: Rect) -> Rect {
+        Rect::new(
+            area.x + self.left,
+            area.y + self.top,
+            area.width.saturating_sub(self.left + self.right),
+            area.height.saturating_sub(self.top + self.bottom),
+        )
+    }
+}
+
+/// The sizes a [`Flex`] layout needs to know about each item, as (width, height).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct FlexItem {
+    pub preferred: (usize, usize),
+    pub min: (usize, usize),
+}
+
+/// Places items one after the other along a direction, like a CSS flexbox.
+///
+/// Items keep their preferred size. When they don't fit and wrapping is off they
+/// are shrunk towards their minimum size, when wrapping is on they continue on
+/// a new line instead.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Flex {
+    direction: Direction,
+    justify_content: JustifyContent,
+    align_items: AlignItems,
+    gap: usize,
+    padding: Padding,
+    wrap: bool,
+}
+
+impl Flex {
+    pub fn new(direction: Direction) -> Self {
+        Self {
+            direction,
+            justify_content: JustifyContent::default(),
+            align_items: AlignItems::default(),
+            gap: 0,
+            padding: Padding::default(),
+            wrap: false,
+        }
+    }
+
+    pub fn row() -> Self {
+        Self::new(Direction::Horizontal)
+    }
+
+    pub fn column() -> Self {
+        Self::new(Direction::Vertical)
+    }
+
+    pub fn justify_content(mut self, justify_content: JustifyContent) -> Self {
+        self.justify_content = justify_content;
+        self
+    }
+    pub fn align_items(mut self, align_items: AlignItems) -> Self {
+        self.align_items = align_items;
+        self
+    }
+    /// Space between items, and between lines when wrapping.
+    pub fn gap(mut self, gap: usize) -> Self {
+        self.gap = gap;
+        self
+    }
+    pub fn padding(mut self, padding: Padding) -> Self {
+        self.padding = padding;
+        self
+    }
+    pub fn wrap(mut self, wrap: bool) -> Self {
+        self.wrap = wrap;
+        self
+    }
+
+    /// Splits a size into its (main axis, cross axis) components.
+    fn axes(&self, (width, height): (usize, usize)) -> (usize, usize) {
+        match self.direction {
+            Direction::Horizontal => (width, height),
+            Direction::Vertical => (height, width),
+        }
+    }
+
+    fn rect(
+        &self,
+        area: Rect,
+        main: usize,
+        cross: usize,
+        main_size: usize,
+        cross_size: usize,
+    ) -> Rect {
+        match self.direction {
+            Direction::Horizontal => {
+                Rect::new(area.x + main, area.y + cross, main_size, cross_size)
+            }
+            Direction::Vertical => Rect::new(area.x + cross, area.y + main, cross_size, main_size),
+        }
+    }
+
+    /// The (width, height) needed to show all items at their preferred size on a single line.
+    pub fn content_size(&self, items: &[FlexItem]) -> (usize, usize) {
+        let main: usize = items
+            .iter()
+            .map(|item| self.axes(item.preferred).0)
+            .sum::<usize>()
+            + self.gap * items.len().saturating_sub(1);
+        let cross = items
+            .iter()
+            .map(|item| self.axes(item.preferred).1)
+            .max()
+            .unwrap_or(0);
+
+        let (width, height) = match self.direction {
+            Direction::Horizontal => (main, cross),
+            Direction::Vertical => (cross, main),
+        };
+        (
+            width + self.padding.left + self.padding.right,
+            height + self.padding.top + self.padding.bottom,
+        )
+    }
+
+    /// Returns the area of each item, in the same order.
+    pub fn arrange(&self, area: Rect, items: &[FlexItem]) -> Vec<Rect> {
+        let inner = self.padding.shrink(area);
+        let (inner_main, inner_cross) = self.axes((inner.width, inner.height));
+
+        let mut rects = Vec::with_capacity(items.len());
+        let mut cross_offset = 0;
+        for line in self.lines(items, inner_main) {
+            let line_items = &items[line.clone()];
+            let mut mains: Vec<usize> = line_items
+                .iter()
+                .map(|item| self.axes(item.preferred).0)
+                .collect();
+            if !self.wrap {
+                self.shrink(&mut mains, line_items, inner_main);
+            }
+
+            let line_cross = if self.wrap {
+                line_items
+                    .iter()
+                    .map(|item| self.axes(item.preferred).1)
+                    .max()
+                    .unwrap_or(0)
+            } else {
+                inner_cross
+            };
+
+            let used = mains.iter().sum::<usize>() + self.gap * mains.len().saturating_sub(1);
+            let (mut main_offset, spacing) =
+                self.distribute(inner_main.saturating_sub(used), mains.len());
+
+            for (i, (item, main_size)) in line_items.iter().zip(mains).enumerate() {
+                let cross_size = match self.align_items {
+                    AlignItems::Stretch => line_cross,
+                    _ => self.axes(item.preferred).1.min(line_cross),
+                };
+                let cross = match self.align_items {
+                    AlignItems::Start | AlignItems::Stretch => 0,
+                    AlignItems::End => line_cross - cross_size,
+                    AlignItems::Center => (line_cross - cross_size) / 2,
+                };
+
+                rects.push(self.rect(
+                    inner,
+                    main_offset,
+                    cross_offset + cross,
+                    main_size,
+                    cross_size,
+                ));
+                main_offset += main_size + self.gap + spacing.get(i).copied().unwrap_or(0);
+            }
+
+            cross_offset += line_cross + self.gap;
+        }
+
+        rects
+    }
+
+    /// Groups the items into lines that fit into `available` space along the main axis.
+    fn lines(&self, items: &[FlexItem], available: usize) -> Vec<std::ops::Range<usize>> {
+        if !self.wrap {
+            return std::iter::once(0..items.len()).collect();
+        }
+
+        let mut lines = Vec::new();
+        let mut start = 0;
+        let mut used = 0;
+        for (i, item) in items.iter().enumerate() {
+            let main = self.axes(item.preferred).0;
+            if i > start && used + self.gap + main > available {
+                lines.push(start..i);
+                start = i;
+                used = main;
+            } else if i == start {
+                used = main;
+            } else {
+                used += self.gap + main;
+            }
+        }
+        if start < items.len() {
+            lines.push(start..items.len());
+        }
+        lines
+    }
+
+    /// Shrinks the items towards their minimum size until they fit into `available`,
+    /// proportionally to how much each of them can shrink.
+    fn shrink(&self, mains: &mut [usize], items: &[FlexItem], available: usize) {
+        let used = mains.iter().sum::<usize>() + self.gap * mains.len().saturating_sub(1);
+        let mut overflow = used.saturating_sub(available);
+        if overflow == 0 {
+            return;
+        }
+
+        let shrinkable: Vec<usize> = mains
+            .iter()
+            .zip(items)
+            .map(|(main, item)| main.saturating_sub(self.axes(item.min).0))
+            .collect();
+        let total: usize = shrinkable.iter().sum();
+        if total == 0 {
+            return;
+        }
+
+        let overflow_total = overflow.min(total);
+        for (main, room) in mains.iter_mut().zip(&shrinkable) {
+            let cut = (overflow_total * room / total).min(overflow);
+            *main -= cut;
+            overflow -= cut;
+        }
+        // Rounding leftovers, taken from the first items that can still shrink.
+        for (main, item) in mains.iter_mut().zip(items) {
+            if overflow == 0 {
+                break;
+            }
+            let room = main.saturating_sub(self.axes(item.min).0);
+            let cut = room.min(overflow);
+            *main -= cut;
+            overflow -= cut;
+        }
+    }
+
+    /// Returns the offset of the first item and the extra space after each item.
+    fn distribute(&self, free: usize, count: usize) -> (usize, Vec<usize>) {
+        if count == 0 {
+            return (0, Vec::new());
+        }
+
+        let spread = |slots: usize| -> Vec<usize> {
+            (0..slots)
+                .map(|i| free / slots + usize::from(i < free % slots))
+                .collect()
+        };
+
+        match self.justify_content {
+            JustifyContent::Start => (0, Vec::new()),
+            JustifyContent::End => (free, Vec::new()),
+            JustifyContent::Center => (free / 2, Vec::new()),
+            JustifyContent::SpaceBetween if count == 1 => (0, Vec::new()),
+            JustifyContent::SpaceBetween => (0, spread(count - 1)),
+            JustifyContent::SpaceAround => {
+                let around = free / count;
+                (around / 2, vec![around; count])
+            }
+            JustifyContent::SpaceEvenly => {
+                let slots = spread(count + 1);
+                (slots[0], slots[1..].to_vec())
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn item(width: usize, height: usize) -> FlexItem {
+        FlexItem {
+            preferred: (width, height),
+            min: (0, 0),
+        }
+    }
+
+    fn xs(rects: &[Rect]) -> Vec<usize> {
+        rects.iter().map(|rect| rect.x).collect()
+    }
+
+    #[test]
+    fn test_row_with_gap_and_padding() {
+        let flex = Flex::row().gap(1).padding(Padding::uniform(1));
+        let rects = flex.arrange(Rect::new(0, 0, 20, 5), &[item(3, 1), item(4, 2)]);
+        assert_eq!(rects, vec![Rect::new(1, 1, 3, 1), Rect::new(5, 1, 4, 2)]);
+    }
+
+    #[test]
+    fn test_column() {
+        let flex = Flex::column().gap(1);
+        let rects = flex.arrange(Rect::new(2, 2, 10, 10), &[item(3, 1), item(4, 2)]);
+        assert_eq!(rects, vec![Rect::new(2, 2, 3, 1), Rect::new(2, 4, 4, 2)]);
+    }
+
+    #[test]
+    fn test_justify_content() {
+        let items = [item(2, 1), item(2, 1)];
+        let area = Rect::new(0, 0, 10, 1);
+        let arrange = |justify| xs(&Flex::row().justify_content(justify).arrange(area, &items));
+
+        assert_eq!(arrange(JustifyContent::Start), vec![0, 2]);
+        assert_eq!(arrange(JustifyContent::End), vec![6, 8]);
+        assert_eq!(arrange(JustifyContent::Center), vec![3, 5]);
+        assert_eq!(arrange(JustifyContent::SpaceBetween), vec![0, 8]);
+        assert_eq!(arrange(JustifyContent::SpaceAround), vec![1, 6]);
+        assert_eq!(arrange(JustifyContent::SpaceEvenly), vec![2, 6]);
+    }
+
+    #[test]
+    fn test_align_items() {
+        let items = [item(2, 1), item(2, 3)];
+        let area = Rect::new(0, 0, 10, 5);
+        let arrange = |align| Flex::row().align_items(align).arrange(area, &items);
+
+        let rects = arrange(AlignItems::Start);
+        assert_eq!((rects[0].y, rects[0].height), (0, 1));
+        let rects = arrange(AlignItems::End);
+        assert_eq!((rects[0].y, rects[1].y), (4, 2));
+        let rects = arrange(AlignItems::Center);
+        assert_eq!((rects[0].y, rects[1].y), (2, 1));
+        let rects = arrange(AlignItems::Stretch);
+        assert_eq!((rects[0].height, rects[1].height), (5, 5));
+    }
+
+    #[test]
+    fn test_wrap() {
+        let flex = Flex::row().wrap(true).gap(1);
+        let rects = flex.arrange(
+            Rect::new(0, 0, 7, 10),
+            &[item(3, 1), item(3, 2), item(3, 1)],
+        );
+        assert_eq!(
+            rects,
+            vec![
+                Rect::new(0, 0, 3, 1),
+                Rect::new(4, 0, 3, 2),
+                Rect::new(0, 3, 3, 1),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_shrink_without_wrap() {
+        let flex = Flex::row();
+        let items = [
+            FlexItem {
+                preferred: (6, 1),
+                min: (2, 1),
+            },
+            FlexItem {
+                preferred: (6, 1),
+                min: (6, 1),
+            },
+        ];
+        let rects = flex.arrange(Rect::new(0, 0, 10, 1), &items);
+        assert_eq!(rects, vec![Rect::new(0, 0, 4, 1), Rect::new(4, 0, 6, 1)]);
+    }
+
+    #[test]
+    fn test_content_size() {
+        let flex = Flex::row().gap(1).padding(Padding::symmetric(1, 2));
+        assert_eq!(flex.content_size(&[item(3, 1), item(4, 2)]), (12, 4));
+        assert_eq!(
+            Flex::column().content_size(&[item(3, 1), item(4, 2)]),
+            (4, 3)
+        );
+    }
+}
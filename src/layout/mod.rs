@@ -0,0 +1,271 @@
+mod flex;
+
+pub use flex::{AlignItems, Flex, FlexItem, JustifyContent, Padding};
+
+use crate::Rect;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Direction {
+    /// Areas are placed next to each other, from left to right.
+    Horizontal,
+    /// Areas are placed below each other, from top to bottom.
+    Vertical,
+}
+
+/// How much space an area of a [`Layout`] takes along the layout's direction.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Constraint {
+    /// Exactly the given number of cells.
+    Length(usize),
+    /// A percentage of the available space.
+    Percentage(u16),
+    /// A fraction of the available space, e.g. `Ratio(1, 3)` for a third.
+    Ratio(u32, u32),
+    /// At least the given number of cells, grows to share the remaining space.
+    Min(usize),
+    /// Shares the remaining space, but takes at most the given number of cells.
+    Max(usize),
+    /// Shares the remaining space proportionally to the given weight.
+    Fill(u16),
+}
+
+impl Constraint {
+    /// The size the area gets before the remaining space is shared.
+    fn base(&self, total: usize) -> usize {
+        match *self {
+            Constraint::Length(length) => length,
+            Constraint::Percentage(percent) => total * percent as usize / 100,
+            Constraint::Ratio(_, 0) => 0,
+            Constraint::Ratio(numerator, denominator) => {
+                total * numerator as usize / denominator as usize
+            }
+            Constraint::Min(min) => min,
+            Constraint::Max(_) | Constraint::Fill(_) => 0,
+        }
+    }
+
+    /// The share of the remaining space this area grows by, 0 for fixed sizes.
+    fn weight(&self) -> usize {
+        match *self {
+            Constraint::Min(_) | Constraint::Max(_) => 1,
+            Constraint::Fill(weight) => weight as usize,
+            _ => 0,
+        }
+    }
+
+    fn cap(&self) -> usize {
+        match *self {
+            Constraint::Max(max) => max,
+            _ => usize::MAX,
+        }
+    }
+}
+
+/// Splits an area into smaller ones, horizontally or vertically, according to a list of constraints.
+///
+/// Fixed sizes (`Length`, `Percentage`, `Ratio` and the minimum of `Min`) are
+/// allocated first, in order, as long as there is space left. The remaining
+/// space is then shared by the `Min`, `Max` and `Fill` areas. If there are none,
+/// the remaining space is left empty at the end.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Layout {
+    direction: Direction,
+    constraints: Vec<Constraint>,
+}
+
+impl Layout {
+    pub fn new(direction: Direction, constraints: Vec<Constraint>) -> Self {
+        Self {
+            direction,
+            constraints,
+        }
+    }
+
+    pub fn horizontal(constraints: Vec<Constraint>) -> Self {
+        Self::new(Direction::Horizontal, constraints)
+    }
+
+    pub fn vertical(constraints: Vec<Constraint>) -> Self {
+        Self::new(Direction::Vertical, constraints)
+    }
+
+    pub fn direction(&self) -> Direction {
+        self.direction
+    }
+
+    pub fn constraints(&self) -> &[Constraint] {
+        &self.constraints
+    }
+
+    /// Returns one area per constraint, in the same order.
+    pub fn split(&self, area: Rect) -> Vec<Rect> {
+        let total = match self.direction {
+            Direction::Horizontal => area.width,
+            Direction::Vertical => area.height,
+        };
+
+        let sizes = solve(&self.constraints, total);
+
+        let mut offset = 0;
+        sizes
+            .into_iter()
+            .map(|size| {
+                let rect = match self.direction {
+                    Direction::Horizontal => Rect::new(area.x + offset, area.y, size, area.height),
+                    Direction::Vertical => Rect::new(area.x, area.y + offset, area.width, size),
+                };
+                offset += size;
+                rect
+            })
+            .collect()
+    }
+}
+
+pub(crate) fn solve(constraints: &[Constraint], total: usize) -> Vec<usize> {
+    let mut remaining = total;
+    let mut sizes: Vec<usize> = constraints
+        .iter()
+        .map(|constraint| {
+            let size = constraint.base(total).min(remaining);
+            remaining -= size;
+            size
+        })
+        .collect();
+
+    // Share what is left between the growing areas, by weight. Areas that reach
+    // their maximum are taken out and the rest is shared again.
+    let mut capped = vec![false; constraints.len()];
+    while remaining > 0 {
+        let active: Vec<usize> = (0..constraints.len())
+            .filter(|i| !capped[*i] && constraints[*i].weight() > 0)
+            .collect();
+        let total_weight: usize = active.iter().map(|i| constraints[*i].weight()).sum();
+        if total_weight == 0 {
+            break;
+        }
+
+        let mut hit_cap = false;
+        for i in active.iter() {
+            let share = remaining * constraints[*i].weight() / total_weight;
+            let room = constraints[*i].cap().saturating_sub(sizes[*i]);
+            if share >= room {
+                sizes[*i] += room;
+                remaining -= room;
+                capped[*i] = true;
+                hit_cap = true;
+            }
+        }
+        if hit_cap {
+            continue;
+        }
+
+        let mut left = remaining;
+        for i in active.iter() {
+            let share = remaining * constraints[*i].weight() / total_weight;
+            sizes[*i] += share;
+            left -= share;
+        }
+        // Hand out what is lost to rounding, one cell at a time, in order.
+        for i in active.iter().cycle().take(left) {
+            sizes[*i] += 1;
+        }
+        remaining = 0;
+    }
+
+    sizes
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn widths(constraints: Vec<Constraint>, total: usize) -> Vec<usize> {
+        Layout::horizontal(constraints)
+            .split(Rect::new(0, 0, total, 1))
+            .iter()
+            .map(|rect| rect.width)
+            .collect()
+    }
+
+    #[test]
+    fn test_fixed_lengths() {
+        assert_eq!(
+            widths(vec![Constraint::Length(3), Constraint::Length(4)], 10),
+            vec![3, 4]
+        );
+    }
+
+    #[test]
+    fn test_lengths_overflow() {
+        assert_eq!(
+            widths(vec![Constraint::Length(6), Constraint::Length(6)], 10),
+            vec![6, 4]
+        );
+    }
+
+    #[test]
+    fn test_percentage_and_ratio() {
+        assert_eq!(
+            widths(
+                vec![Constraint::Percentage(50), Constraint::Ratio(1, 4)],
+                20
+            ),
+            vec![10, 5]
+        );
+    }
+
+    #[test]
+    fn test_fill() {
+        assert_eq!(
+            widths(
+                vec![
+                    Constraint::Length(2),
+                    Constraint::Fill(1),
+                    Constraint::Fill(2)
+                ],
+                11
+            ),
+            vec![2, 3, 6]
+        );
+    }
+
+    #[test]
+    fn test_fill_rounding() {
+        assert_eq!(widths(vec![Constraint::Fill(1); 3], 10), vec![4, 3, 3]);
+    }
+
+    #[test]
+    fn test_min() {
+        assert_eq!(
+            widths(vec![Constraint::Min(3), Constraint::Length(2)], 10),
+            vec![8, 2]
+        );
+        assert_eq!(
+            widths(vec![Constraint::Min(3), Constraint::Fill(1)], 5),
+            vec![4, 1]
+        );
+    }
+
+    #[test]
+    fn test_max() {
+        assert_eq!(
+            widths(vec![Constraint::Max(3), Constraint::Fill(1)], 10),
+            vec![3, 7]
+        );
+        assert_eq!(widths(vec![Constraint::Max(3)], 10), vec![3]);
+    }
+
+    #[test]
+    fn test_vertical_positions() {
+        let rects = Layout::vertical(vec![Constraint::Length(1), Constraint::Fill(1)])
+            .split(Rect::new(2, 3, 10, 5));
+        assert_eq!(rects, vec![Rect::new(2, 3, 10, 1), Rect::new(2, 4, 10, 4)]);
+    }
+
+    #[test]
+    fn test_horizontal_positions() {
+        let rects = Layout::horizontal(vec![Constraint::Length(4), Constraint::Fill(1)])
+            .split(Rect::new(1, 1, 10, 3));
+        assert_eq!(rects, vec![Rect::new(1, 1, 4, 3), Rect::new(5, 1, 6, 3)]);
+    }
+}
@@ -1,15 +1,43 @@
 pub mod buffer;
+pub mod element_list;
 pub mod elements;
 pub mod error;
+pub mod event;
+pub mod harness;
+pub mod input;
+pub mod layout;
+pub mod raw_mode;
+pub mod rect;
 pub mod rectext;
+pub mod session;
+pub mod span;
+pub mod style;
 pub mod traits;
+pub mod tree;
 
-pub use buffer::Buffer;
-pub use elements::{Container, Rectangle, Text};
+pub use buffer::{Buffer, Cell};
+pub use element_list::ElementList;
+pub use elements::{
+    Alignment, Button, Checkbox, Container, Grid, GridArea, RadioGroup, Rectangle, ScrollView,
+    Text, TextArea, TextInput, Toggle, Track, VerticalAlignment, Wrap,
+};
 pub use error::Error;
+pub use event::{Event, KeyCode, KeyModifiers, MouseButton, MouseEventKind, MouseMode};
+pub use harness::Harness;
+pub use layout::{
+    AlignItems, Constraint, Direction, Flex, FlexItem, JustifyContent, Layout, Padding,
+};
+pub use raw_mode::RawMode;
+pub use rect::Rect;
 pub use rectext::Rectext;
-pub use terminal::TerminalCommand;
+pub use session::TerminalSession;
+pub use span::{Line, Span};
+pub use style::{Attributes, Color, Style};
+pub use terminal::{CursorShape, TerminalCommand};
 pub use traits::UIElement;
+pub use tree::{PostOrder, PreOrder};
 
 mod terminal;
+#[cfg(test)]
+mod testing;
 use terminal::Terminal;
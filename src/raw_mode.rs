@@ -0,0 +1,232 @@
+use termios::Termios;
+
+/// Describes which line discipline features of the terminal are turned off.
+///
+/// Start from one of the presets and adjust it with the builder methods:
+///
+/// ```no_run
+/// use rectext::RawMode;
+///
+/// // Raw mode, but Ctrl-C still sends SIGINT.
+/// let mode = RawMode::raw().signals(true);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RawMode {
+    echo: bool,
+    canonical: bool,
+    signals: bool,
+    flow_control: bool,
+    input_processing: bool,
+    output_processing: bool,
+    extended_input: bool,
+    vmin: u8,
+    vtime: u8,
+}
+
+impl RawMode {
+    /// Input is available byte by byte and is not echoed, but Ctrl-C/Ctrl-Z,
+    /// flow control and CR/NL translation keep working.
+    pub fn cbreak() -> Self {
+        Self {
+            echo: false,
+            canonical: false,
+            signals: true,
+            flow_control: true,
+            input_processing: true,
+            output_processing: true,
+            extended_input: true,
+            vmin: 1,
+            vtime: 0,
+        }
+    }
+
+    /// The same settings as `cfmakeraw(3)`, every key including Ctrl-C, Ctrl-Z
+    /// and Ctrl-S/Ctrl-Q is delivered as input.
+    pub fn raw() -> Self {
+        Self {
+            echo: false,
+            canonical: false,
+            signals: false,
+            flow_control: false,
+            input_processing: false,
+            output_processing: false,
+            extended_input: false,
+            vmin: 1,
+            vtime: 0,
+        }
+    }
+
+    /// Like [`RawMode::raw`], but Ctrl-C, Ctrl-Z and Ctrl-\ still generate signals.
+    pub fn raw_keep_signals() -> Self {
+        Self::raw().signals(true)
+    }
+
+    /// The usual line buffered, echoing mode of a shell.
+    pub fn cooked() -> Self {
+        Self {
+            echo: true,
+            canonical: true,
+            ..Self::cbreak()
+        }
+    }
+
+    /// `ECHO`: whether typed characters are echoed.
+    pub fn echo(mut self, enable: bool) -> Self {
+        self.echo = enable;
+        self
+    }
+    /// `ICANON`: whether input is buffered line by line.
+    pub fn canonical(mut self, enable: bool) -> Self {
+        self.canonical = enable;
+        self
+    }
+    /// `ISIG`: whether Ctrl-C, Ctrl-Z and Ctrl-\ generate signals instead of input.
+    pub fn signals(mut self, enable: bool) -> Self {
+        self.signals = enable;
+        self
+    }
+    /// `IXON`: whether Ctrl-S/Ctrl-Q pause and resume the output.
+    pub fn flow_control(mut self, enable: bool) -> Self {
+        self.flow_control = enable;
+        self
+    }
+    /// `ICRNL` and friends: whether CR is translated to NL on input, breaks are
+    /// turned into signals and the 8th bit is stripped.
+    pub fn input_processing(mut self, enable: bool) -> Self {
+        self.input_processing = enable;
+        self
+    }
+    /// `OPOST`: whether output is post-processed, e.g. NL translated to CR NL.
+    pub fn output_processing(mut self, enable: bool) -> Self {
+        self.output_processing = enable;
+        self
+    }
+    /// `IEXTEN`: whether implementation defined input processing like Ctrl-V is enabled.
+    pub fn extended_input(mut self, enable: bool) -> Self {
+        self.extended_input = enable;
+        self
+    }
+    /// `VMIN`: the minimum number of bytes a blocking read waits for.
+    pub fn vmin(mut self, bytes: u8) -> Self {
+        self.vmin = bytes;
+        self
+    }
+    /// `VTIME`: the read timeout in tenths of a second, 0 waits forever.
+    pub fn vtime(mut self, deciseconds: u8) -> Self {
+        self.vtime = deciseconds;
+        self
+    }
+
+    /// Applies the mode to `termios`, flags not covered by the mode are left untouched.
+    pub fn apply(&self, termios: &mut Termios) {
+        fn set(flags: &mut termios::tcflag_t, mask: termios::tcflag_t, enable: bool) {
+            if enable {
+                *flags |= mask;
+            } else {
+                *flags &= !mask;
+            }
+        }
+
+        set(&mut termios.c_lflag, termios::ECHO, self.echo);
+        set(&mut termios.c_lflag, termios::ICANON, self.canonical);
+        set(&mut termios.c_lflag, termios::ISIG, self.signals);
+        set(&mut termios.c_lflag, termios::IEXTEN, self.extended_input);
+        set(&mut termios.c_iflag, termios::IXON, self.flow_control);
+        set(&mut termios.c_iflag, termios::ICRNL, self.input_processing);
+        set(&mut termios.c_oflag, termios::OPOST, self.output_processing);
+
+        if !self.input_processing {
+            termios.c_iflag &= !(termios::IGNBRK
+                | termios::BRKINT
+                | termios::PARMRK
+                | termios::ISTRIP
+                | termios::INLCR
+                | termios::IGNCR);
+            termios.c_lflag &= !termios::ECHONL;
+            termios.c_cflag &= !(termios::CSIZE | termios::PARENB);
+            termios.c_cflag |= termios::CS8;
+        }
+
+        termios.c_cc[termios::VMIN] = self.vmin;
+        termios.c_cc[termios::VTIME] = self.vtime;
+    }
+}
+
+impl Default for RawMode {
+    fn default() -> Self {
+        Self::cbreak()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn cooked_termios() -> Termios {
+        let mut termios: Termios = unsafe { std::mem::zeroed() };
+        RawMode::cooked().apply(&mut termios);
+        termios
+    }
+
+    #[test]
+    fn test_cbreak() {
+        let mut termios = cooked_termios();
+        RawMode::cbreak().apply(&mut termios);
+
+        assert_eq!(termios.c_lflag & termios::ECHO, 0);
+        assert_eq!(termios.c_lflag & termios::ICANON, 0);
+        assert_ne!(termios.c_lflag & termios::ISIG, 0);
+        assert_ne!(termios.c_iflag & termios::IXON, 0);
+        assert_ne!(termios.c_iflag & termios::ICRNL, 0);
+        assert_ne!(termios.c_oflag & termios::OPOST, 0);
+        assert_eq!(termios.c_cc[termios::VMIN], 1);
+    }
+
+    #[test]
+    fn test_raw() {
+        let mut termios = cooked_termios();
+        termios.c_iflag |= termios::ISTRIP | termios::BRKINT;
+        RawMode::raw().apply(&mut termios);
+
+        assert_eq!(termios.c_lflag & (termios::ECHO | termios::ICANON), 0);
+        assert_eq!(termios.c_lflag & (termios::ISIG | termios::IEXTEN), 0);
+        assert_eq!(termios.c_iflag & (termios::IXON | termios::ICRNL), 0);
+        assert_eq!(termios.c_iflag & (termios::ISTRIP | termios::BRKINT), 0);
+        assert_eq!(termios.c_oflag & termios::OPOST, 0);
+        assert_eq!(termios.c_cflag & termios::CSIZE, termios::CS8);
+    }
+
+    #[test]
+    fn test_raw_keep_signals() {
+        let mut termios = cooked_termios();
+        RawMode::raw_keep_signals().apply(&mut termios);
+
+        assert_ne!(termios.c_lflag & termios::ISIG, 0);
+        assert_eq!(termios.c_iflag & termios::IXON, 0);
+    }
+
+    #[test]
+    fn test_builder() {
+        let mut termios = cooked_termios();
+        RawMode::raw()
+            .flow_control(true)
+            .vmin(0)
+            .vtime(2)
+            .apply(&mut termios);
+
+        assert_ne!(termios.c_iflag & termios::IXON, 0);
+        assert_eq!(termios.c_cc[termios::VMIN], 0);
+        assert_eq!(termios.c_cc[termios::VTIME], 2);
+    }
+
+    #[test]
+    fn test_cooked_restores() {
+        let mut termios = cooked_termios();
+        RawMode::raw().apply(&mut termios);
+        RawMode::cooked().apply(&mut termios);
+
+        assert_ne!(termios.c_lflag & (termios::ECHO | termios::ICANON), 0);
+        assert_ne!(termios.c_lflag & termios::ISIG, 0);
+        assert_ne!(termios.c_oflag & termios::OPOST, 0);
+    }
+}
@@ -0,0 +1,82 @@
+/// An area of cells, (x, y) being its top left corner.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Rect {
+    pub x: usize,
+    pub y: usize,
+    pub width: usize,
+    pub height: usize,
+}
+
+impl Rect {
+    pub fn new(x: usize, y: usize, width: usize, height: usize) -> Self {
+        Self {
+            x,
+            y,
+            width,
+            height,
+        }
+    }
+
+    /// The first column to the right of the area.
+    pub fn right(&self) -> usize {
+        self.x + self.width
+    }
+
+    /// The first row below the area.
+    pub fn bottom(&self) -> usize {
+        self.y + self.height
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.width == 0 || self.height == 0
+    }
+
+    pub fn contains(&self, x: usize, y: usize) -> bool {
+        x >= self.x && x < self.right() && y >= self.y && y < self.bottom()
+    }
+
+    /// The area covered by both rectangles, empty if they don't overlap.
+    pub fn intersection(&self, other: &Rect) -> Rect {
+        let x = self.x.max(other.x);
+        let y = self.y.max(other.y);
+        let right = self.right().min(other.right());
+        let bottom = self.bottom().min(other.bottom());
+
+        Rect {
+            x,
+            y,
+            width: right.saturating_sub(x),
+            height: bottom.saturating_sub(y),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_contains() {
+        let rect = Rect::new(2, 3, 4, 5);
+        assert!(rect.contains(2, 3));
+        assert!(rect.contains(5, 7));
+        assert!(!rect.contains(6, 7));
+        assert!(!rect.contains(5, 8));
+        assert!(!rect.contains(1, 3));
+    }
+
+    #[test]
+    fn test_intersection() {
+        let a = Rect::new(0, 0, 10, 10);
+        let b = Rect::new(5, 8, 10, 10);
+        assert_eq!(a.intersection(&b), Rect::new(5, 8, 5, 2));
+        assert_eq!(b.intersection(&a), Rect::new(5, 8, 5, 2));
+    }
+
+    #[test]
+    fn test_intersection_disjoint() {
+        let a = Rect::new(0, 0, 5, 5);
+        let b = Rect::new(10, 10, 5, 5);
+        assert!(a.intersection(&b).is_empty());
+    }
+}
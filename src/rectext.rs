@@ -1,12 +1,16 @@
 use std::{
     cell::RefCell,
-    collections::HashMap,
     io::{Read, Write},
     os::fd::AsRawFd,
     rc::Rc,
 };
 
-use crate::{Buffer, Error, Terminal, TerminalCommand, UIElement};
+use crate::{
+    terminal::terminal_size,
+    tree::{check_id, descend, hit_test, PostOrder, PreOrder, PATH_SEPARATOR},
+    Buffer, ElementList, Error, Event, KeyCode, KeyModifiers, Layout, MouseEventKind, Rect, Style,
+    Terminal, TerminalCommand, UIElement,
+};
 
 pub struct Rectext<T, U>
 where
@@ -17,7 +21,19 @@ where
     height: usize,
     front_buffer: Buffer,
     back_buffer: Buffer,
-    elements: HashMap<String, Rc<RefCell<dyn UIElement>>>,
+    elements: ElementList,
+    layout: Option<Layout>,
+    full_repaint: bool,
+    strict: bool,
+    /// Where the terminal cursor was shown after the last draw, if it was shown.
+    cursor: Option<(usize, usize)>,
+    /// The path of the element that gets key events.
+    focused: Option<String>,
+    focus_order: Option<Vec<String>>,
+    /// The path of the element that gets mouse events until the button is released.
+    captured: Option<String>,
+    /// The path of the element under the mouse pointer.
+    hovered: Option<String>,
     pub terminal: Terminal<T, U>,
 }
 
@@ -33,41 +49,453 @@ where
             height,
             front_buffer: Buffer::new(width, height),
             back_buffer: Buffer::new(width, height),
-            elements: HashMap::new(),
+            elements: ElementList::new(),
+            layout: None,
+            full_repaint: false,
+            strict: false,
+            cursor: None,
+            focused: None,
+            focus_order: None,
+            captured: None,
+            hovered: None,
             terminal,
         }
     }
 
-    pub fn add_element(&mut self, id: String, element: Rc<RefCell<dyn UIElement>>) {
+    /// Creates a new instance with the current size of the terminal `stdout` refers to.
+    pub fn with_terminal_size(stdin: T, stdout: U) -> Result<Self, Error> {
+        let (width, height) = terminal_size(stdout.as_raw_fd())?;
+        Ok(Self::new(width, height, stdin, stdout))
+    }
+
+    /// Reallocates the buffers for the new size, the next [`Rectext::draw`]
+    /// clears the screen and repaints every cell.
+    pub fn resize(&mut self, width: usize, height: usize) {
+        self.width = width;
+        self.height = height;
+        self.front_buffer = Buffer::new(width, height);
+        self.back_buffer = Buffer::new(width, height);
+        self.terminal.resize(width, height);
+        self.full_repaint = true;
+        self.apply_layout();
+    }
+
+    /// Positions and sizes the top level elements with a [`Layout`] of the whole
+    /// screen, which is re-applied whenever the screen is resized. The n-th
+    /// element, in drawing order, gets the n-th area.
+    pub fn set_layout(&mut self, layout: Option<Layout>) {
+        self.layout = layout;
+        self.apply_layout();
+    }
+
+    fn apply_layout(&self) {
+        let Some(layout) = &self.layout else {
+            return;
+        };
+        let areas = layout.split(Rect::new(0, 0, self.width, self.height));
+        for (element, area) in self.elements.values().zip(areas) {
+            let mut element = element.borrow_mut();
+            element.set_position(area.x, area.y);
+            element.set_size(area.width, area.height);
+        }
+    }
+
+    /// What the last [`Rectext::draw`] put on the screen.
+    pub fn screen(&self) -> &Buffer {
+        &self.front_buffer
+    }
+
+    /// Returns the (width, height) of the drawing area.
+    pub fn size(&self) -> (usize, usize) {
+        (self.width, self.height)
+    }
+
+    /// In strict mode, elements drawing outside of the screen or of their parent's
+    /// bounds make [`Rectext::draw`] fail instead of being clipped. Useful for debugging layouts.
+    pub fn set_strict(&mut self, strict: bool) {
+        self.strict = strict;
+    }
+
+    /// Adds a top level element on top of the others. The id must not contain
+    /// [`PATH_SEPARATOR`], otherwise [`Error::InvalidId`] is returned.
+    pub fn add_element(
+        &mut self,
+        id: String,
+        element: Rc<RefCell<dyn UIElement>>,
+    ) -> Result<(), Error> {
+        check_id(&id)?;
         self.elements.insert(id, element);
+        self.apply_layout();
+        Ok(())
+    }
+
+    /// Removes the element at `path` and returns it. A path is a list of ids
+    /// separated by `/`, e.g. `"panel/header/title"`, so nested elements can be
+    /// removed as well.
+    pub fn remove_element(&mut self, path: &str) -> Option<Rc<RefCell<dyn UIElement>>> {
+        let removed = self.find(path)?;
+        match path.rsplit_once(PATH_SEPARATOR) {
+            Some((parent, id)) => self
+                .find(parent)?
+                .borrow_mut()
+                .remove_sub_element(id)
+                .ok()?,
+            None => {
+                self.elements.remove(path);
+                self.apply_layout();
+            }
+        }
+
+        // The focused element may be the removed one or inside of it, so it is
+        // looked up in the removed subtree.
+        if let Some(focused) = self.focused.take_if(|focused| is_within(focused, path)) {
+            let element = match focused[path.len()..].strip_prefix(PATH_SEPARATOR) {
+                Some(rest) => descend(removed.clone(), rest),
+                None => Some(removed.clone()),
+            };
+            if let Some(element) = element {
+                element.borrow_mut().set_focused(false);
+            }
+        }
+        for tracked in [&mut self.captured, &mut self.hovered] {
+            if tracked
+                .as_deref()
+                .is_some_and(|tracked| is_within(tracked, path))
+            {
+                *tracked = None;
+            }
+        }
+        Some(removed)
+    }
+
+    /// The top level element with the given id.
+    pub fn get_element(&self, id: &str) -> Option<Rc<RefCell<dyn UIElement>>> {
+        self.elements.get(id).cloned()
+    }
+
+    /// Finds an element by its path, a list of ids separated by `/` starting at
+    /// a top level element, e.g. `"panel/header/title"`.
+    pub fn find(&self, path: &str) -> Option<Rc<RefCell<dyn UIElement>>> {
+        match path.split_once(PATH_SEPARATOR) {
+            Some((id, rest)) => descend(self.get_element(id)?, rest),
+            None => self.get_element(path),
+        }
     }
 
-    pub fn remove_element(&mut self, id: &str) {
-        self.elements.remove(id);
+    /// The ids of the top level elements, in drawing order.
+    pub fn element_ids(&self) -> Vec<String> {
+        self.elements.ids().cloned().collect()
     }
 
+    fn roots(&self) -> Vec<(String, Rc<RefCell<dyn UIElement>>)> {
+        self.elements
+            .iter()
+            .map(|(id, element)| (id.clone(), element.clone()))
+            .collect()
+    }
+
+    /// Iterates over the whole element tree as (path, element), parents before their sub elements.
+    pub fn iter_pre_order(&self) -> PreOrder {
+        PreOrder::new(self.roots())
+    }
+
+    /// Iterates over the whole element tree as (path, element), sub elements before their parent.
+    pub fn iter_post_order(&self) -> PostOrder {
+        PostOrder::new(self.roots())
+    }
+
+    /// The path of the focused element, if any.
+    pub fn focused(&self) -> Option<&str> {
+        self.focused.as_deref()
+    }
+
+    /// Moves the focus to the element at `path`. Returns false, leaving the
+    /// focus where it was, if there is no such element or it isn't focusable.
+    pub fn focus(&mut self, path: &str) -> bool {
+        let Some(element) = self.find(path) else {
+            return false;
+        };
+        if !element.borrow().is_focusable() {
+            return false;
+        }
+        self.blur();
+        element.borrow_mut().set_focused(true);
+        self.focused = Some(path.to_string());
+        true
+    }
+
+    /// Removes the focus from the focused element, if any.
+    pub fn blur(&mut self) {
+        if let Some(element) = self.focused.take().and_then(|path| self.find(&path)) {
+            element.borrow_mut().set_focused(false);
+        }
+    }
+
+    /// The paths of the elements Tab moves the focus through, in order, instead
+    /// of all focusable elements in drawing order. Paths that don't lead to a
+    /// focusable element are skipped.
+    pub fn set_focus_order(&mut self, order: Option<Vec<String>>) {
+        self.focus_order = order;
+    }
+
+    fn focusable_paths(&self) -> Vec<String> {
+        let focusable = |element: &Rc<RefCell<dyn UIElement>>| element.borrow().is_focusable();
+        match &self.focus_order {
+            Some(order) => order
+                .iter()
+                .filter(|path| self.find(path).is_some_and(|element| focusable(&element)))
+                .cloned()
+                .collect(),
+            None => self
+                .iter_pre_order()
+                .filter(|(_, element)| focusable(element))
+                .map(|(path, _)| path)
+                .collect(),
+        }
+    }
+
+    /// Moves the focus to the next focusable element, wrapping around at the end.
+    /// Returns false if there is no focusable element.
+    pub fn focus_next(&mut self) -> bool {
+        self.cycle_focus(true)
+    }
+
+    /// Moves the focus to the previous focusable element, wrapping around at the start.
+    /// Returns false if there is no focusable element.
+    pub fn focus_prev(&mut self) -> bool {
+        self.cycle_focus(false)
+    }
+
+    fn cycle_focus(&mut self, forward: bool) -> bool {
+        let paths = self.focusable_paths();
+        let len = paths.len();
+        if len == 0 {
+            return false;
+        }
+        let current = self
+            .focused
+            .as_ref()
+            .and_then(|focused| paths.iter().position(|path| path == focused));
+        let next = match (current, forward) {
+            (Some(i), true) => (i + 1) % len,
+            (Some(i), false) => (i + len - 1) % len,
+            (None, true) => 0,
+            (None, false) => len - 1,
+        };
+        self.focus(&paths[next])
+    }
+
+    /// The path of the topmost element at (x, y). Sub elements are only found
+    /// within the bounds of their parent, like they are drawn.
+    pub fn element_at(&self, x: usize, y: usize) -> Option<String> {
+        hit_test(&self.roots(), x, y).map(|(path, _, _)| path)
+    }
+
+    /// The absolute position of the element at `path`, negative if it is
+    /// scrolled past the top or left edge of the screen.
+    fn absolute_position(&self, path: &str) -> Option<(isize, isize)> {
+        let mut ids = path.split(PATH_SEPARATOR);
+        let mut element = self.get_element(ids.next()?)?;
+        let (mut x, mut y) = (0, 0);
+        for id in ids {
+            let next = {
+                let parent = element.borrow();
+                let (parent_x, parent_y) = parent.get_position();
+                let (offset_x, offset_y) = parent.content_offset();
+                x += parent_x as isize - offset_x;
+                y += parent_y as isize - offset_y;
+                parent.get_sub_element(id)?
+            };
+            element = next;
+        }
+        let (element_x, element_y) = element.borrow().get_position();
+        Some((x + element_x as isize, y + element_y as isize))
+    }
+
+    /// Sends `event` to the element at `path`, then to its parents until one of
+    /// them uses it. Mouse positions are made relative to each element.
+    /// Returns the path of the element that used the event.
+    fn bubble(&self, path: &str, event: &Event) -> Option<String> {
+        let mut path = path;
+        loop {
+            if let Some(element) = self.find(path) {
+                let event = match *event {
+                    Event::Mouse {
+                        kind,
+                        button,
+                        x,
+                        y,
+                        modifiers,
+                    } => {
+                        let (element_x, element_y) = self.absolute_position(path)?;
+                        Event::Mouse {
+                            kind,
+                            button,
+                            x: x - element_x,
+                            y: y - element_y,
+                            modifiers,
+                        }
+                    }
+                    _ => event.clone(),
+                };
+                if element.borrow_mut().handle_event(&event) {
+                    return Some(path.to_string());
+                }
+            }
+            path = path.rsplit_once(PATH_SEPARATOR)?.0;
+        }
+    }
+
+    /// Handles an input event and returns whether it was used.
+    ///
+    /// Key and paste events go to the focused element. If it doesn't use an
+    /// event, it is passed to its parent, and so on up to the top level element.
+    /// Tab and Shift+Tab that no element used move the focus.
+    ///
+    /// Mouse events go to the topmost element under the pointer and bubble up
+    /// the same way, with positions relative to each element. Pressing a button
+    /// focuses the nearest focusable element, and the element that used the
+    /// press gets all drag and release events until the button is released,
+    /// even outside of its bounds, where positions to its left or above it are negative.
+    /// Elements are told when the pointer enters or leaves them with
+    /// [`UIElement::set_hovered`].
+    pub fn handle_event(&mut self, event: &Event) -> bool {
+        match event {
+            Event::Key { .. } | Event::Paste(_) => self.handle_key(event),
+            Event::Mouse { .. } => self.handle_mouse(event),
+            _ => false,
+        }
+    }
+
+    fn handle_key(&mut self, event: &Event) -> bool {
+        let focused = self.focused.clone();
+        if focused.is_some_and(|focused| self.bubble(&focused, event).is_some()) {
+            return true;
+        }
+        match event {
+            Event::Key {
+                code: KeyCode::Tab,
+                modifiers,
+            } if *modifiers == KeyModifiers::NONE => self.focus_next(),
+            Event::Key {
+                code: KeyCode::BackTab,
+                ..
+            } => self.focus_prev(),
+            Event::Key {
+                code: KeyCode::Tab,
+                modifiers,
+            } if *modifiers == KeyModifiers::SHIFT => self.focus_prev(),
+            _ => false,
+        }
+    }
+
+    fn handle_mouse(&mut self, event: &Event) -> bool {
+        let Event::Mouse { kind, x, y, .. } = *event else {
+            return false;
+        };
+        // Only synthetic events can be off the screen, there is nothing there.
+        let hit = match (usize::try_from(x), usize::try_from(y)) {
+            (Ok(x), Ok(y)) => self.element_at(x, y),
+            _ => None,
+        };
+        self.hover(hit.clone());
+
+        if let Some(captured) = self.captured.clone() {
+            match kind {
+                MouseEventKind::Drag => return self.bubble(&captured, event).is_some(),
+                MouseEventKind::Release => {
+                    self.captured = None;
+                    return self.bubble(&captured, event).is_some();
+                }
+                _ => {}
+            }
+        }
+
+        let Some(path) = hit else {
+            return false;
+        };
+        if kind == MouseEventKind::Press {
+            let mut focusable = path.as_str();
+            while !self.focus(focusable) {
+                let Some((parent, _)) = focusable.rsplit_once(PATH_SEPARATOR) else {
+                    break;
+                };
+                focusable = parent;
+            }
+        }
+        let used_by = self.bubble(&path, event);
+        if kind == MouseEventKind::Press {
+            self.captured = used_by.clone();
+        }
+        used_by.is_some()
+    }
+
+    fn hover(&mut self, path: Option<String>) {
+        if self.hovered == path {
+            return;
+        }
+        if let Some(element) = self.hovered.take().and_then(|path| self.find(&path)) {
+            element.borrow_mut().set_hovered(false);
+        }
+        if let Some(element) = path.as_ref().and_then(|path| self.find(path)) {
+            element.borrow_mut().set_hovered(true);
+        }
+        self.hovered = path;
+    }
+
+    /// Moves an element one step up in the drawing order, returns false if there is no such element.
+    pub fn raise_element(&mut self, id: &str) -> bool {
+        self.elements.raise(id)
+    }
+
+    /// Moves an element one step down in the drawing order, returns false if there is no such element.
+    pub fn lower_element(&mut self, id: &str) -> bool {
+        self.elements.lower(id)
+    }
+
+    /// Draws an element on top of all the others, returns false if there is no such element.
+    pub fn bring_to_front(&mut self, id: &str) -> bool {
+        self.elements.bring_to_front(id)
+    }
+
+    /// Draws an element below all the others, returns false if there is no such element.
+    pub fn send_to_back(&mut self, id: &str) -> bool {
+        self.elements.send_to_back(id)
+    }
+
+    /// Draws all elements and updates the cells of the terminal that changed.
+    ///
+    /// If an element asked for the cursor with [`Buffer::set_cursor`], e.g. a
+    /// focused [`crate::TextInput`], the terminal cursor is moved there and shown,
+    /// otherwise it is hidden again.
     pub fn draw(&mut self) -> Result<(), Error> {
         self.back_buffer.clear();
+        self.back_buffer.set_strict(self.strict);
 
         for element in self.elements.values() {
             element.borrow().draw(&mut self.back_buffer)?;
         }
 
-        self.render()?;
+        let printed = self.render()?;
+        self.place_cursor(printed)?;
 
         self.terminal.flush()?;
+        self.full_repaint = false;
 
         std::mem::swap(&mut self.front_buffer, &mut self.back_buffer);
 
         Ok(())
     }
 
-    fn render(&mut self) -> Result<(), Error> {
-        let mut gaps = 0;
-        let mut first_gap = 0;
+    /// Prints the cells that changed, returns whether anything was printed.
+    fn render(&mut self) -> Result<bool, Error> {
+        // Index of the cell the terminal cursor is currently at, if known.
+        let mut cursor = None;
+        let mut printed = false;
 
-        self.terminal
-            .buffer_cmd(TerminalCommand::MoveCursorTo(1, 1))?;
+        if self.full_repaint {
+            self.terminal.buffer_cmd(TerminalCommand::ClearScreen)?;
+        }
 
         for ((i, b_cell), f_cell) in self
             .back_buffer
@@ -75,27 +503,668 @@ where
             .enumerate()
             .zip(self.front_buffer.iter())
         {
-            if *b_cell == *f_cell {
-                if gaps == 0 {
-                    first_gap = i;
-                }
-                gaps += 1;
+            // Continuation cells are printed along with the double-width glyph before them.
+            if b_cell.is_continuation() || (*b_cell == *f_cell && !self.full_repaint) {
+                continue;
+            }
+
+            if cursor != Some(i) {
+                // Column
+                let x = (i % self.width) + 1;
+                // Row
+                let y = (i / self.width) + 1;
+                self.terminal
+                    .buffer_cmd(TerminalCommand::MoveCursorTo(x, y))?;
+            }
+            self.terminal
+                .buffer_cmd(TerminalCommand::SetStyle(b_cell.style))?;
+            self.terminal
+                .buffer_cmd(TerminalCommand::PrintStr(&b_cell.symbol))?;
+            printed = true;
+
+            let next = i + b_cell.width();
+            // Don't rely on the terminal wrapping to the next row.
+            cursor = if next % self.width == 0 {
+                None
             } else {
-                if gaps != 0 {
-                    // Column
-                    let x = ((first_gap + gaps) % self.width) + 1;
-                    // Row
-                    let y = ((first_gap + gaps) / self.width) + 1;
+                Some(next)
+            };
+        }
+
+        self.terminal
+            .buffer_cmd(TerminalCommand::SetStyle(Style::default()))?;
+
+        Ok(printed)
+    }
+
+    fn place_cursor(&mut self, printed: bool) -> Result<(), Error> {
+        let cursor = self.back_buffer.cursor();
+        match cursor {
+            Some((x, y)) => {
+                // Printing moves the cursor away from where it was left.
+                if printed || self.cursor != cursor {
                     self.terminal
-                        .buffer_cmd(TerminalCommand::MoveCursorTo(x, y))?;
-                    gaps = 0;
-                    first_gap = 0;
+                        .buffer_cmd(TerminalCommand::MoveCursorTo(x + 1, y + 1))?;
+                }
+                if self.cursor.is_none() {
+                    self.terminal.buffer_cmd(TerminalCommand::UnhideCursor)?;
                 }
-                self.terminal
-                    .buffer_cmd(TerminalCommand::PrintChar(*b_cell))?;
             }
+            None if self.cursor.is_some() => {
+                self.terminal.buffer_cmd(TerminalCommand::HideCursor)?;
+            }
+            None => {}
         }
+        self.cursor = cursor;
+        Ok(())
+    }
+}
+
+/// Whether `path` is `ancestor` or one of its sub elements.
+fn is_within(path: &str, ancestor: &str) -> bool {
+    path.strip_prefix(ancestor)
+        .is_some_and(|rest| rest.is_empty() || rest.starts_with(PATH_SEPARATOR))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        testing::MockOutput, Button, Checkbox, Color, Constraint, Container, MouseButton,
+        RadioGroup, Rectangle, ScrollView, Text, TextInput,
+    };
+    use std::fs::File;
+
+    fn mock_rectext(width: usize, height: usize) -> (Rectext<File, MockOutput>, MockOutput) {
+        let output = MockOutput::default();
+        let stdin = File::open("/dev/null").unwrap();
+        (Rectext::new(width, height, stdin, output.clone()), output)
+    }
+
+    #[test]
+    fn test_draw_only_changes() -> Result<(), Error> {
+        let (mut rectext, output) = mock_rectext(5, 2);
+        let text = Rc::new(RefCell::new(Text::new(1, 1, "ab")));
+        rectext.add_element("text".to_string(), text.clone())?;
+
+        rectext.draw()?;
+        assert_eq!(output.take(), "\x1b[2;2Hab");
+
+        text.borrow_mut().set_content("ac");
+        rectext.draw()?;
+        assert_eq!(output.take(), "\x1b[2;3Hc");
+
+        rectext.draw()?;
+        assert_eq!(output.take(), "");
+        Ok(())
+    }
+
+    #[test]
+    fn test_draw_places_cursor() -> Result<(), Error> {
+        let (mut rectext, output) = mock_rectext(5, 1);
+        let input = Rc::new(RefCell::new(TextInput::new(0, 0, 5).value("ab")));
+        input.borrow_mut().set_focused(true);
+        rectext.add_element("input".to_string(), input.clone())?;
+
+        rectext.draw()?;
+        assert_eq!(output.take(), "\x1b[1;1Hab\x1b[1;3H\x1b[?25h");
+        rectext.draw()?;
+        assert_eq!(output.take(), "");
+
+        let left = Event::key(KeyCode::Left, KeyModifiers::NONE);
+        input.borrow_mut().handle_event(&left);
+        rectext.draw()?;
+        assert_eq!(output.take(), "\x1b[1;2H");
+
+        input.borrow_mut().set_focused(false);
+        rectext.draw()?;
+        assert_eq!(output.take(), "\x1b[?25l");
+        Ok(())
+    }
+
+    #[test]
+    fn test_draw_styles() -> Result<(), Error> {
+        let (mut rectext, output) = mock_rectext(5, 1);
+        let red = Style::new().fg(Color::Red);
+        let text = Text::new(0, 0, "ab").style(red);
+        rectext.add_element("text".to_string(), Rc::new(RefCell::new(text)))?;
+
+        rectext.draw()?;
+        assert_eq!(output.take(), "\x1b[1;1H\x1b[31mab\x1b[0m");
+        Ok(())
+    }
+
+    #[test]
+    fn test_draw_wide_chars() -> Result<(), Error> {
+        let (mut rectext, output) = mock_rectext(6, 1);
+        let text = Rc::new(RefCell::new(Text::new(0, 0, "日本x")));
+        rectext.add_element("text".to_string(), text.clone())?;
+
+        rectext.draw()?;
+        assert_eq!(output.take(), "\x1b[1;1H日本x");
+
+        text.borrow_mut().set_content("a本x");
+        rectext.draw()?;
+        assert_eq!(output.take(), "\x1b[1;1Ha本x ");
+        Ok(())
+    }
+
+    #[test]
+    fn test_draw_clips_overflowing_elements() -> Result<(), Error> {
+        let (mut rectext, output) = mock_rectext(4, 1);
+        let text = Text::new(2, 0, "abcd");
+        rectext.add_element("text".to_string(), Rc::new(RefCell::new(text)))?;
+
+        rectext.draw()?;
+        assert_eq!(output.take(), "\x1b[1;3Hab");
+
+        rectext.set_strict(true);
+        assert!(rectext.draw().is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_overlapping_elements_draw_in_order() -> Result<(), Error> {
+        let (mut rectext, output) = mock_rectext(3, 1);
+        // Enough elements that a hash map would be unlikely to keep them in order.
+        for c in 'a'..='z' {
+            let text = Text::new(0, 0, &c.to_string().repeat(3));
+            rectext.add_element(c.to_string(), Rc::new(RefCell::new(text)))?;
+        }
+
+        rectext.draw()?;
+        assert_eq!(output.take(), "\x1b[1;1Hzzz");
+
+        assert!(rectext.bring_to_front("m"));
+        rectext.draw()?;
+        assert_eq!(output.take(), "\x1b[1;1Hmmm");
+
+        assert!(rectext.lower_element("m"));
+        rectext.draw()?;
+        assert_eq!(output.take(), "\x1b[1;1Hzzz");
+
+        assert!(rectext.send_to_back("z"));
+        assert!(rectext.raise_element("a"));
+        rectext.draw()?;
+        assert_eq!(output.take(), "\x1b[1;1Hmmm");
+        Ok(())
+    }
+
+    #[test]
+    fn test_layout_reflows_on_resize() -> Result<(), Error> {
+        let (mut rectext, _) = mock_rectext(10, 5);
+        rectext.set_layout(Some(Layout::vertical(vec![
+            Constraint::Length(1),
+            Constraint::Fill(1),
+        ])));
+
+        let header = Rc::new(RefCell::new(Rectangle::new(0, 0, 1, 1)));
+        let body = Rc::new(RefCell::new(Container::new(0, 0, 1, 1).layout(
+            Layout::horizontal(vec![Constraint::Percentage(50), Constraint::Fill(1)]),
+        )));
+        let right = Rc::new(RefCell::new(Rectangle::new(0, 0, 1, 1)));
+        body.borrow_mut().add_sub_element(
+            "left".to_string(),
+            Rc::new(RefCell::new(Text::new(0, 0, ""))),
+        )?;
+        body.borrow_mut()
+            .add_sub_element("right".to_string(), right.clone())?;
+        rectext.add_element("header".to_string(), header.clone())?;
+        rectext.add_element("body".to_string(), body.clone())?;
+
+        assert_eq!(body.borrow().get_position(), (0, 1));
+        assert_eq!(right.borrow().get_position(), (5, 0));
+
+        rectext.resize(20, 10);
+        assert_eq!(header.borrow().get_position(), (0, 0));
+        assert_eq!(body.borrow().get_position(), (0, 1));
+        assert_eq!(right.borrow().get_position(), (10, 0));
+        Ok(())
+    }
+
+    fn tree(rectext: &mut Rectext<File, MockOutput>) -> Rc<RefCell<Text>> {
+        let panel = Rc::new(RefCell::new(Container::new(0, 0, 10, 5)));
+        let header = Rc::new(RefCell::new(Rectangle::new(0, 0, 10, 1)));
+        let title = Rc::new(RefCell::new(Text::new(0, 0, "Title")));
+        header
+            .borrow_mut()
+            .add_sub_element("title".to_string(), title.clone())
+            .unwrap();
+        panel
+            .borrow_mut()
+            .add_sub_element("header".to_string(), header)
+            .unwrap();
+        panel
+            .borrow_mut()
+            .add_sub_element(
+                "body".to_string(),
+                Rc::new(RefCell::new(Text::new(0, 1, "Body"))),
+            )
+            .unwrap();
+        rectext.add_element("panel".to_string(), panel).unwrap();
+        rectext
+            .add_element(
+                "footer".to_string(),
+                Rc::new(RefCell::new(Text::new(0, 4, "Footer"))),
+            )
+            .unwrap();
+        title
+    }
+
+    fn paths(iter: impl Iterator<Item = (String, Rc<RefCell<dyn UIElement>>)>) -> Vec<String> {
+        iter.map(|(path, _)| path).collect()
+    }
+
+    #[test]
+    fn test_find() {
+        let (mut rectext, _) = mock_rectext(10, 5);
+        let title = tree(&mut rectext);
+
+        let found = rectext.find("panel/header/title").unwrap();
+        assert!(Rc::ptr_eq(&found, &(title as Rc<RefCell<dyn UIElement>>)));
+        assert!(rectext.find("footer").is_some());
+        assert!(rectext.find("panel/missing").is_none());
+        assert!(rectext.find("footer/title").is_none());
+        assert_eq!(
+            rectext.find("panel").unwrap().borrow().sub_element_ids(),
+            vec!["header", "body"]
+        );
+    }
+
+    #[test]
+    fn test_traversal() {
+        let (mut rectext, _) = mock_rectext(10, 5);
+        tree(&mut rectext);
+
+        assert_eq!(
+            paths(rectext.iter_pre_order()),
+            vec![
+                "panel",
+                "panel/header",
+                "panel/header/title",
+                "panel/body",
+                "footer"
+            ]
+        );
+        assert_eq!(
+            paths(rectext.iter_post_order()),
+            vec![
+                "panel/header/title",
+                "panel/header",
+                "panel/body",
+                "panel",
+                "footer"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_remove_nested_element() {
+        let (mut rectext, _) = mock_rectext(10, 5);
+        tree(&mut rectext);
+
+        assert!(rectext.remove_element("panel/header/title").is_some());
+        assert!(rectext.find("panel/header/title").is_none());
+        assert!(rectext.remove_element("panel/header/title").is_none());
+
+        assert!(rectext.remove_element("panel").is_some());
+        assert_eq!(rectext.element_ids(), vec!["footer"]);
+    }
+
+    #[test]
+    fn test_ids_must_not_contain_the_separator() {
+        let (mut rectext, _) = mock_rectext(10, 5);
+        tree(&mut rectext);
+        let text = Rc::new(RefCell::new(Text::new(0, 0, "x")));
+
+        assert!(matches!(
+            rectext.add_element("panel/body".to_string(), text.clone()),
+            Err(Error::InvalidId(id)) if id == "panel/body"
+        ));
+        assert!(matches!(
+            rectext
+                .find("panel")
+                .unwrap()
+                .borrow_mut()
+                .add_sub_element("header/title".to_string(), text),
+            Err(Error::InvalidId(_))
+        ));
+        assert_eq!(rectext.element_ids(), vec!["panel", "footer"]);
+    }
+
+    #[test]
+    fn test_resize_repaints_everything() -> Result<(), Error> {
+        let (mut rectext, output) = mock_rectext(2, 1);
+        rectext.draw()?;
+        assert_eq!(output.take(), "");
+
+        rectext.resize(3, 1);
+        rectext.draw()?;
+        assert_eq!(output.take(), "\x1b[2J\x1b[1;1H   ");
+        assert_eq!(rectext.size(), (3, 1));
+        Ok(())
+    }
+
+    /// A form with a text input, a checkbox nested one level deeper and a button.
+    fn form(rectext: &mut Rectext<File, MockOutput>) -> Rc<RefCell<Checkbox>> {
+        let wrap = Rc::new(RefCell::new(Checkbox::new(0, 0, "Wrap")));
+        let mut options = Container::new(0, 1, 10, 1);
+        options
+            .add_sub_element("wrap".to_string(), wrap.clone())
+            .unwrap();
+        let mut form = Container::new(0, 0, 10, 5);
+        let name = TextInput::new(0, 0, 10);
+        form.add_sub_element("name".to_string(), Rc::new(RefCell::new(name)))
+            .unwrap();
+        form.add_sub_element("options".to_string(), Rc::new(RefCell::new(options)))
+            .unwrap();
+        let ok = Button::new(0, 2, "OK");
+        form.add_sub_element("ok".to_string(), Rc::new(RefCell::new(ok)))
+            .unwrap();
+
+        let title = Text::new(0, 0, "x");
+        rectext
+            .add_element("title".to_string(), Rc::new(RefCell::new(title)))
+            .unwrap();
+        rectext
+            .add_element("form".to_string(), Rc::new(RefCell::new(form)))
+            .unwrap();
+        wrap
+    }
+
+    #[test]
+    fn test_tab_moves_focus_in_tree_order() {
+        let (mut rectext, _) = mock_rectext(10, 5);
+        let wrap = form(&mut rectext);
+        let tab = Event::key(KeyCode::Tab, KeyModifiers::NONE);
+        let back_tab = Event::key(KeyCode::BackTab, KeyModifiers::NONE);
+
+        assert_eq!(rectext.focused(), None);
+        assert!(rectext.handle_event(&tab));
+        assert_eq!(rectext.focused(), Some("form/name"));
+        assert!(rectext.handle_event(&tab));
+        assert_eq!(rectext.focused(), Some("form/options/wrap"));
+        assert!(wrap.borrow().is_focused());
+        assert!(rectext.handle_event(&tab));
+        assert_eq!(rectext.focused(), Some("form/ok"));
+        assert!(!wrap.borrow().is_focused());
+        assert!(rectext.handle_event(&tab));
+        assert_eq!(rectext.focused(), Some("form/name"));
+
+        assert!(rectext.handle_event(&back_tab));
+        assert_eq!(rectext.focused(), Some("form/ok"));
+        let shift_tab = Event::key(KeyCode::Tab, KeyModifiers::SHIFT);
+        assert!(rectext.handle_event(&shift_tab));
+        assert_eq!(rectext.focused(), Some("form/options/wrap"));
+    }
+
+    #[test]
+    fn test_explicit_focus_order() {
+        let (mut rectext, _) = mock_rectext(10, 5);
+        form(&mut rectext);
+        rectext.set_focus_order(Some(vec![
+            "form/ok".to_string(),
+            "title".to_string(),
+            "form/name".to_string(),
+        ]));
+
+        assert!(rectext.focus_next());
+        assert_eq!(rectext.focused(), Some("form/ok"));
+        assert!(rectext.focus_next());
+        assert_eq!(rectext.focused(), Some("form/name"));
+        assert!(rectext.focus_prev());
+        assert_eq!(rectext.focused(), Some("form/ok"));
+
+        assert!(!rectext.focus("title"));
+        assert!(!rectext.focus("form/missing"));
+        assert_eq!(rectext.focused(), Some("form/ok"));
+    }
+
+    #[test]
+    fn test_key_events_go_to_focused_element() {
+        let (mut rectext, _) = mock_rectext(10, 5);
+        let wrap = form(&mut rectext);
+        let space = Event::key(KeyCode::Char(' '), KeyModifiers::NONE);
+
+        assert!(!rectext.handle_event(&space));
+        assert!(rectext.focus("form/options/wrap"));
+        assert!(rectext.handle_event(&space));
+        assert!(wrap.borrow().is_checked());
+
+        // A path that doesn't exist removes nothing and keeps the focus.
+        assert!(rectext.remove_element("form/opt").is_none());
+        assert_eq!(rectext.focused(), Some("form/options/wrap"));
+        assert!(wrap.borrow().is_focused());
+
+        rectext.remove_element("form/options");
+        assert_eq!(rectext.focused(), None);
+        assert!(!wrap.borrow().is_focused());
+    }
+
+    #[test]
+    fn test_unused_events_bubble_up() -> Result<(), Error> {
+        let (mut rectext, _) = mock_rectext(10, 5);
+        let log = Rc::new(RefCell::new(
+            ScrollView::new(0, 0, 10, 2).content_size(10, 20),
+        ));
+        let filter = Rc::new(RefCell::new(TextInput::new(0, 0, 10)));
+        log.borrow_mut()
+            .add_sub_element("filter".to_string(), filter.clone())?;
+        rectext.add_element("log".to_string(), log.clone())?;
+        assert!(rectext.focus("log/filter"));
+
+        // The input uses characters, but not Down, which scrolls the view instead.
+        assert!(rectext.handle_event(&Event::key(KeyCode::Char('a'), KeyModifiers::NONE)));
+        assert!(rectext.handle_event(&Event::key(KeyCode::Down, KeyModifiers::NONE)));
+        assert_eq!(filter.borrow().get_value(), "a");
+        assert_eq!(log.borrow().scroll_offset(), (0, 1));
+
+        assert!(!rectext.handle_event(&Event::key(KeyCode::F(1), KeyModifiers::NONE)));
+        Ok(())
+    }
+
+    #[test]
+    fn test_scroll_view_takes_focus() {
+        let (mut rectext, _) = mock_rectext(10, 5);
+        let log = Rc::new(RefCell::new(
+            ScrollView::new(0, 0, 10, 2).content_size(10, 20),
+        ));
+        rectext.add_element("log".to_string(), log.clone()).unwrap();
+
+        assert!(rectext.handle_event(&Event::key(KeyCode::Tab, KeyModifiers::NONE)));
+        assert_eq!(rectext.focused(), Some("log"));
+        assert!(rectext.handle_event(&Event::key(KeyCode::End, KeyModifiers::NONE)));
+        assert_eq!(log.borrow().scroll_offset(), (0, 18));
+        assert!(!rectext.handle_event(&Event::key(KeyCode::Home, KeyModifiers::CTRL)));
+        assert_eq!(log.borrow().scroll_offset(), (0, 18));
+    }
+
+    /// An element that records the events and hover changes it gets.
+    struct Probe {
+        bounds: Rect,
+        log: Rc<RefCell<Vec<String>>>,
+    }
+
+    impl UIElement for Probe {
+        fn draw(&self, _buffer: &mut Buffer) -> Result<(), Error> {
+            Ok(())
+        }
+        fn get_position(&self) -> (usize, usize) {
+            (self.bounds.x, self.bounds.y)
+        }
+        fn set_position(&mut self, x: usize, y: usize) {
+            self.bounds.x = x;
+            self.bounds.y = y;
+        }
+        fn get_size(&self) -> (usize, usize) {
+            (self.bounds.width, self.bounds.height)
+        }
+        fn set_hovered(&mut self, hovered: bool) {
+            let change = if hovered { "enter" } else { "leave" };
+            self.log.borrow_mut().push(change.to_string());
+        }
+        fn handle_event(&mut self, event: &Event) -> bool {
+            let Event::Mouse { kind, x, y, .. } = event else {
+                return false;
+            };
+            self.log.borrow_mut().push(format!("{kind:?} {x},{y}"));
+            true
+        }
+    }
+
+    fn probe(x: usize, y: usize, width: usize, height: usize) -> (Probe, Rc<RefCell<Vec<String>>>) {
+        let log = Rc::new(RefCell::new(Vec::new()));
+        let bounds = Rect::new(x, y, width, height);
+        (
+            Probe {
+                bounds,
+                log: log.clone(),
+            },
+            log,
+        )
+    }
+
+    fn mouse(kind: MouseEventKind, x: isize, y: isize) -> Event {
+        let button = match kind {
+            MouseEventKind::Moved => MouseButton::None,
+            _ => MouseButton::Left,
+        };
+        Event::mouse(kind, button, x, y)
+    }
+
+    #[test]
+    fn test_element_at() -> Result<(), Error> {
+        let (mut rectext, _) = mock_rectext(10, 5);
+        let mut panel = Container::new(2, 1, 4, 3);
+        // Reaches past the panel, the part outside of it is clipped.
+        panel.add_sub_element(
+            "wide".to_string(),
+            Rc::new(RefCell::new(probe(1, 1, 8, 1).0)),
+        )?;
+        rectext.add_element("panel".to_string(), Rc::new(RefCell::new(panel)))?;
+        let mut view = ScrollView::new(5, 0, 3, 2).content_size(3, 10);
+        view.add_sub_element(
+            "row".to_string(),
+            Rc::new(RefCell::new(probe(0, 4, 3, 1).0)),
+        )?;
+        view.scroll_to(0, 3);
+        rectext.add_element("view".to_string(), Rc::new(RefCell::new(view)))?;
+
+        assert_eq!(rectext.element_at(2, 1).as_deref(), Some("panel"));
+        assert_eq!(rectext.element_at(3, 2).as_deref(), Some("panel/wide"));
+        assert_eq!(rectext.element_at(7, 2), None);
+        // The view is drawn on top of the panel.
+        assert_eq!(rectext.element_at(5, 1).as_deref(), Some("view/row"));
+        assert_eq!(rectext.element_at(5, 0).as_deref(), Some("view"));
+
+        rectext.bring_to_front("panel");
+        assert_eq!(rectext.element_at(5, 1).as_deref(), Some("panel"));
+        assert_eq!(rectext.element_at(5, 2).as_deref(), Some("panel/wide"));
+        Ok(())
+    }
+
+    #[test]
+    fn test_element_at_inside_a_border() -> Result<(), Error> {
+        let (mut rectext, _) = mock_rectext(10, 5);
+        let mut view = ScrollView::new(0, 0, 5, 4).border(true);
+        view.add_sub_element(
+            "row".to_string(),
+            Rc::new(RefCell::new(probe(0, 0, 3, 1).0)),
+        )?;
+        rectext.add_element("view".to_string(), Rc::new(RefCell::new(view)))?;
+
+        assert_eq!(rectext.element_at(0, 0).as_deref(), Some("view"));
+        assert_eq!(rectext.element_at(1, 1).as_deref(), Some("view/row"));
+        assert_eq!(rectext.element_at(3, 1).as_deref(), Some("view/row"));
+        assert_eq!(rectext.element_at(4, 1).as_deref(), Some("view"));
+        Ok(())
+    }
+
+    #[test]
+    fn test_mouse_positions_are_relative() -> Result<(), Error> {
+        let (mut rectext, _) = mock_rectext(10, 6);
+        let group = Rc::new(RefCell::new(RadioGroup::new(1, 1, &["a", "b", "c"])));
+        let mut panel = Container::new(2, 2, 8, 4);
+        panel.add_sub_element("size".to_string(), group.clone())?;
+        rectext.add_element("panel".to_string(), Rc::new(RefCell::new(panel)))?;
+
+        assert!(rectext.handle_event(&mouse(MouseEventKind::Press, 3, 5)));
+        assert_eq!(group.borrow().get_selected(), Some(2));
+        assert_eq!(rectext.focused(), Some("panel/size"));
+
+        // Nothing uses a click on the empty part of the panel.
+        assert!(!rectext.handle_event(&mouse(MouseEventKind::Press, 9, 2)));
+        Ok(())
+    }
+
+    #[test]
+    fn test_press_captures_the_mouse() {
+        let (mut rectext, _) = mock_rectext(10, 5);
+        let (handle, log) = probe(2, 2, 2, 1);
+        rectext
+            .add_element("handle".to_string(), Rc::new(RefCell::new(handle)))
+            .unwrap();
+
+        assert!(rectext.handle_event(&mouse(MouseEventKind::Press, 3, 2)));
+        assert!(rectext.remove_element("handle/missing").is_none());
+        assert!(rectext.handle_event(&mouse(MouseEventKind::Drag, 7, 4)));
+        assert!(rectext.handle_event(&mouse(MouseEventKind::Drag, 0, 0)));
+        assert!(rectext.handle_event(&mouse(MouseEventKind::Release, 8, 3)));
+        // The capture ended with the release.
+        assert!(!rectext.handle_event(&mouse(MouseEventKind::Drag, 8, 3)));
+
+        // The pointer left the handle while it was dragged, but it kept getting the events.
+        assert_eq!(
+            *log.borrow(),
+            [
+                "enter",
+                "Press 1,0",
+                "leave",
+                "Drag 5,2",
+                "Drag -2,-2",
+                "Release 6,1"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_hover_enter_and_leave() {
+        let (mut rectext, _) = mock_rectext(10, 5);
+        let (left, left_log) = probe(0, 0, 2, 1);
+        let (right, right_log) = probe(2, 0, 2, 1);
+        rectext
+            .add_element("left".to_string(), Rc::new(RefCell::new(left)))
+            .unwrap();
+        rectext
+            .add_element("right".to_string(), Rc::new(RefCell::new(right)))
+            .unwrap();
+
+        rectext.handle_event(&mouse(MouseEventKind::Moved, 1, 0));
+        rectext.handle_event(&mouse(MouseEventKind::Moved, 0, 0));
+        rectext.handle_event(&mouse(MouseEventKind::Moved, 3, 0));
+        rectext.handle_event(&mouse(MouseEventKind::Moved, 5, 3));
+
+        assert_eq!(
+            *left_log.borrow(),
+            ["enter", "Moved 1,0", "Moved 0,0", "leave"]
+        );
+        assert_eq!(*right_log.borrow(), ["enter", "Moved 1,0", "leave"]);
+    }
+
+    #[test]
+    fn test_wheel_bubbles_to_scroll_view() -> Result<(), Error> {
+        let (mut rectext, _) = mock_rectext(10, 5);
+        let mut view = ScrollView::new(0, 0, 10, 3);
+        let log = Rc::new(RefCell::new(Text::new(0, 0, &"line\n".repeat(10))));
+        view.add_sub_element("log".to_string(), log)?;
+        let view = Rc::new(RefCell::new(view));
+        rectext.add_element("view".to_string(), view.clone())?;
 
+        let wheel = Event::mouse(MouseEventKind::ScrollDown, MouseButton::None, 1, 1);
+        assert!(rectext.handle_event(&wheel));
+        assert_eq!(view.borrow().scroll_offset(), (0, 3));
+        // The text is scrolled as well when looking for the element under the mouse.
+        assert_eq!(rectext.element_at(0, 0).as_deref(), Some("view/log"));
         Ok(())
     }
 }
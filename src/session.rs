@@ -0,0 +1,216 @@
+use std::{
+    os::fd::{AsRawFd, BorrowedFd, RawFd},
+    panic,
+    sync::{Mutex, Once},
+};
+
+use nix::{
+    fcntl::{
+        fcntl,
+        FcntlArg::{F_GETFL, F_SETFL},
+        OFlag,
+    },
+    unistd,
+};
+use termios::Termios;
+
+use crate::{Error, RawMode};
+
+const ENTER_SEQUENCE: &str = "\x1b[?1049h\x1b[?25l";
+/// Also turns off bracketed paste, focus reporting and mouse reporting, in case
+/// the application left them on.
+const LEAVE_SEQUENCE: &str =
+    "\x1b[0m\x1b[?2004l\x1b[?1004l\x1b[?1006l\x1b[?1003l\x1b[?1002l\x1b[?1000l\x1b[?9l\x1b[?25h\x1b[?1049l";
+
+struct SavedState {
+    stdin_fd: RawFd,
+    stdout_fd: RawFd,
+    termios: Termios,
+    flags: OFlag,
+    /// The number of sessions alive, the state is restored when the last one ends.
+    sessions: usize,
+}
+
+/// The state to restore, shared with the panic hook which has no access to the guard.
+static SAVED_STATE: Mutex<Option<SavedState>> = Mutex::new(None);
+static PANIC_HOOK: Once = Once::new();
+
+/// Puts the terminal into [`RawMode`], switches to the alternate screen and hides
+/// the cursor for as long as it is alive.
+///
+/// The original `Termios` and the file status flags of stdin are saved and
+/// restored when the session is dropped, including when the stack unwinds
+/// because of a panic or an early `?` return. A panic hook is installed as well,
+/// so that the terminal is usable again before the panic message is printed.
+///
+/// Sessions can be nested, only the end of the outermost one restores the terminal.
+pub struct TerminalSession {
+    active: bool,
+}
+
+impl TerminalSession {
+    /// Starts a session using [`RawMode::default`].
+    pub fn enter(stdin: &impl AsRawFd, stdout: &impl AsRawFd) -> Result<Self, Error> {
+        Self::enter_with(stdin, stdout, RawMode::default())
+    }
+
+    pub fn enter_with(
+        stdin: &impl AsRawFd,
+        stdout: &impl AsRawFd,
+        mode: RawMode,
+    ) -> Result<Self, Error> {
+        let stdin_fd = stdin.as_raw_fd();
+        let stdout_fd = stdout.as_raw_fd();
+
+        let termios = Termios::from_fd(stdin_fd)?;
+        let flags = OFlag::from_bits_truncate(fcntl(stdin_fd, F_GETFL)?);
+
+        {
+            let mut saved = SAVED_STATE.lock().unwrap_or_else(|err| err.into_inner());
+            match saved.as_mut() {
+                // A nested session keeps the state saved by the outermost one.
+                Some(state) => state.sessions += 1,
+                None => {
+                    *saved = Some(SavedState {
+                        stdin_fd,
+                        stdout_fd,
+                        termios,
+                        flags,
+                        sessions: 1,
+                    })
+                }
+            }
+        }
+
+        PANIC_HOOK.call_once(|| {
+            let previous = panic::take_hook();
+            panic::set_hook(Box::new(move |info| {
+                restore();
+                previous(info);
+            }));
+        });
+
+        // Ends the session again if anything below fails.
+        let session = Self { active: true };
+
+        let mut raw = termios;
+        mode.apply(&mut raw);
+        termios::tcsetattr(stdin_fd, termios::TCSANOW, &raw)?;
+        write_all(stdout_fd, ENTER_SEQUENCE)?;
+
+        Ok(session)
+    }
+
+    /// Ends the session, restoring the terminal to the state it was in before
+    /// the outermost session started unless other sessions are still alive.
+    /// Called automatically on drop, calling it more than once has no effect.
+    pub fn restore(&mut self) {
+        if !std::mem::take(&mut self.active) {
+            return;
+        }
+        {
+            let mut saved = SAVED_STATE.lock().unwrap_or_else(|err| err.into_inner());
+            if let Some(state) = saved.as_mut() {
+                state.sessions -= 1;
+                if state.sessions > 0 {
+                    return;
+                }
+            }
+        }
+        restore();
+    }
+}
+
+impl Drop for TerminalSession {
+    fn drop(&mut self) {
+        self.restore();
+    }
+}
+
+/// Restores the saved state no matter how many sessions are alive, as the panic hook needs to.
+fn restore() {
+    let Some(state) = SAVED_STATE
+        .lock()
+        .unwrap_or_else(|err| err.into_inner())
+        .take()
+    else {
+        return;
+    };
+
+    // Best effort, there is nothing sensible to do with errors while restoring.
+    let _ = write_all(state.stdout_fd, LEAVE_SEQUENCE);
+    let _ = termios::tcsetattr(state.stdin_fd, termios::TCSANOW, &state.termios);
+    let _ = fcntl(state.stdin_fd, F_SETFL(state.flags));
+}
+
+fn write_all(fd: RawFd, content: &str) -> Result<(), Error> {
+    // The session does not own the descriptor, it is only borrowed for the write.
+    let fd = unsafe { BorrowedFd::borrow_raw(fd) };
+    let mut bytes = content.as_bytes();
+    while !bytes.is_empty() {
+        match unistd::write(fd, bytes) {
+            Ok(n) => bytes = &bytes[n..],
+            Err(nix::errno::Errno::EINTR) => {}
+            Err(err) => return Err(err.into()),
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use nix::pty::openpty;
+    use std::fs::File;
+
+    /// The saved state is global, tests that touch it must not run at the same time.
+    static LOCK: Mutex<()> = Mutex::new(());
+
+    fn lock() -> std::sync::MutexGuard<'static, ()> {
+        LOCK.lock().unwrap_or_else(|err| err.into_inner())
+    }
+
+    #[test]
+    fn test_enter_requires_terminal() -> Result<(), Error> {
+        let _lock = lock();
+        let stdin = File::open("/dev/null")?;
+        let stdout = File::create("/dev/null")?;
+
+        assert!(TerminalSession::enter(&stdin, &stdout).is_err());
+        assert!(SAVED_STATE.lock().unwrap().is_none());
+        Ok(())
+    }
+
+    #[test]
+    fn test_restore_without_session() {
+        let _lock = lock();
+        restore();
+        assert!(SAVED_STATE.lock().unwrap().is_none());
+    }
+
+    #[test]
+    fn test_nested_sessions() -> Result<(), Error> {
+        let _lock = lock();
+        let pty = openpty(None, None)?;
+        let original = Termios::from_fd(pty.slave.as_raw_fd())?;
+        let is_raw = || -> Result<bool, Error> {
+            let current = Termios::from_fd(pty.slave.as_raw_fd())?;
+            Ok(current.c_lflag & termios::ICANON == 0)
+        };
+
+        let mut outer = TerminalSession::enter(&pty.slave, &pty.slave)?;
+        let mut inner = TerminalSession::enter(&pty.slave, &pty.slave)?;
+        inner.restore();
+        assert!(is_raw()?);
+        // Already ended, must not end the outer session as well.
+        inner.restore();
+        drop(inner);
+        assert!(is_raw()?);
+
+        outer.restore();
+        assert!(!is_raw()?);
+        assert_eq!(Termios::from_fd(pty.slave.as_raw_fd())?, original);
+        assert!(SAVED_STATE.lock().unwrap().is_none());
+        Ok(())
+    }
+}
@@ -0,0 +1,93 @@
+use crate::{buffer::str_width, Style};
+
+/// A piece of text drawn with a single style.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct Span {
+    pub content: String,
+    pub style: Style,
+}
+
+impl Span {
+    /// A span without a style of its own, drawn with the style of its [`crate::Text`].
+    pub fn raw(content: &str) -> Self {
+        Self::styled(content, Style::default())
+    }
+
+    pub fn styled(content: &str, style: Style) -> Self {
+        Self {
+            content: content.to_string(),
+            style,
+        }
+    }
+
+    /// The number of columns the span takes.
+    pub fn width(&self) -> usize {
+        str_width(&self.content)
+    }
+}
+
+impl From<&str> for Span {
+    fn from(content: &str) -> Self {
+        Self::raw(content)
+    }
+}
+
+impl From<String> for Span {
+    fn from(content: String) -> Self {
+        Self {
+            content,
+            style: Style::default(),
+        }
+    }
+}
+
+/// A line of text made of spans.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct Line {
+    pub spans: Vec<Span>,
+}
+
+impl Line {
+    pub fn new(spans: Vec<Span>) -> Self {
+        Self { spans }
+    }
+
+    /// The number of columns the line takes.
+    pub fn width(&self) -> usize {
+        self.spans.iter().map(Span::width).sum()
+    }
+}
+
+impl From<Vec<Span>> for Line {
+    fn from(spans: Vec<Span>) -> Self {
+        Self::new(spans)
+    }
+}
+
+impl From<Span> for Line {
+    fn from(span: Span) -> Self {
+        Self::new(vec![span])
+    }
+}
+
+impl From<&str> for Line {
+    fn from(content: &str) -> Self {
+        Self::from(Span::raw(content))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Color;
+
+    #[test]
+    fn test_line_width() {
+        let line = Line::new(vec![
+            Span::raw("ab"),
+            Span::styled("日本", Style::new().fg(Color::Red)),
+        ]);
+        assert_eq!(line.width(), 6);
+        assert_eq!(Line::from("abc").spans, vec![Span::raw("abc")]);
+    }
+}
@@ -0,0 +1,311 @@
+use std::ops::{BitOr, BitOrAssign};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Color {
+    /// The terminal's default color.
+    #[default]
+    Reset,
+    Black,
+    Red,
+    Green,
+    Yellow,
+    Blue,
+    Magenta,
+    Cyan,
+    White,
+    BrightBlack,
+    BrightRed,
+    BrightGreen,
+    BrightYellow,
+    BrightBlue,
+    BrightMagenta,
+    BrightCyan,
+    BrightWhite,
+    /// One of the 256 colors of the xterm palette.
+    Indexed(u8),
+    /// 24-bit truecolor.
+    Rgb(u8, u8, u8),
+}
+
+impl Color {
+    fn push_sgr(&self, codes: &mut Vec<String>, background: bool) {
+        let offset = if background { 10 } else { 0 };
+        let code = match *self {
+            Color::Reset => 39 + offset,
+            Color::Black => 30 + offset,
+            Color::Red => 31 + offset,
+            Color::Green => 32 + offset,
+            Color::Yellow => 33 + offset,
+            Color::Blue => 34 + offset,
+            Color::Magenta => 35 + offset,
+            Color::Cyan => 36 + offset,
+            Color::White => 37 + offset,
+            Color::BrightBlack => 90 + offset,
+            Color::BrightRed => 91 + offset,
+            Color::BrightGreen => 92 + offset,
+            Color::BrightYellow => 93 + offset,
+            Color::BrightBlue => 94 + offset,
+            Color::BrightMagenta => 95 + offset,
+            Color::BrightCyan => 96 + offset,
+            Color::BrightWhite => 97 + offset,
+            Color::Indexed(i) => {
+                codes.push(format!("{};5;{}", 38 + offset, i));
+                return;
+            }
+            Color::Rgb(r, g, b) => {
+                codes.push(format!("{};2;{};{};{}", 38 + offset, r, g, b));
+                return;
+            }
+        };
+        codes.push(code.to_string());
+    }
+}
+
+/// A set of text attributes such as bold or underline.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Attributes(u16);
+
+impl Attributes {
+    pub const NONE: Attributes = Attributes(0);
+    pub const BOLD: Attributes = Attributes(1 << 0);
+    pub const DIM: Attributes = Attributes(1 << 1);
+    pub const ITALIC: Attributes = Attributes(1 << 2);
+    pub const UNDERLINE: Attributes = Attributes(1 << 3);
+    pub const BLINK: Attributes = Attributes(1 << 4);
+    pub const REVERSE: Attributes = Attributes(1 << 5);
+    pub const HIDDEN: Attributes = Attributes(1 << 6);
+    pub const STRIKETHROUGH: Attributes = Attributes(1 << 7);
+
+    /// (attribute, SGR code to enable it, SGR code to disable it)
+    const SGR: [(Attributes, u8, u8); 8] = [
+        (Attributes::BOLD, 1, 22),
+        (Attributes::DIM, 2, 22),
+        (Attributes::ITALIC, 3, 23),
+        (Attributes::UNDERLINE, 4, 24),
+        (Attributes::BLINK, 5, 25),
+        (Attributes::REVERSE, 7, 27),
+        (Attributes::HIDDEN, 8, 28),
+        (Attributes::STRIKETHROUGH, 9, 29),
+    ];
+
+    pub fn contains(&self, other: Attributes) -> bool {
+        self.0 & other.0 == other.0
+    }
+
+    pub fn insert(&mut self, other: Attributes) {
+        self.0 |= other.0;
+    }
+
+    pub fn remove(&mut self, other: Attributes) {
+        self.0 &= !other.0;
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0 == 0
+    }
+}
+
+impl BitOr for Attributes {
+    type Output = Self;
+
+    fn bitor(self, rhs: Self) -> Self::Output {
+        Attributes(self.0 | rhs.0)
+    }
+}
+
+impl BitOrAssign for Attributes {
+    fn bitor_assign(&mut self, rhs: Self) {
+        self.0 |= rhs.0;
+    }
+}
+
+/// Foreground color, background color and attributes of a single cell.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Style {
+    pub fg: Color,
+    pub bg: Color,
+    pub attributes: Attributes,
+}
+
+impl Style {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn fg(mut self, color: Color) -> Self {
+        self.fg = color;
+        self
+    }
+    pub fn bg(mut self, color: Color) -> Self {
+        self.bg = color;
+        self
+    }
+    /// Replaces the attributes, use [`Style::add_attributes`] to combine them.
+    pub fn attributes(mut self, attributes: Attributes) -> Self {
+        self.attributes = attributes;
+        self
+    }
+    /// Adds `attributes` to the ones already set.
+    pub fn add_attributes(mut self, attributes: Attributes) -> Self {
+        self.attributes |= attributes;
+        self
+    }
+
+    /// Applies `other` on top of `self`: its colors replace the ones of `self`
+    /// unless they are [`Color::Reset`], and its attributes are added.
+    pub fn patch(&self, other: Style) -> Style {
+        Style {
+            fg: if other.fg == Color::Reset {
+                self.fg
+            } else {
+                other.fg
+            },
+            bg: if other.bg == Color::Reset {
+                self.bg
+            } else {
+                other.bg
+            },
+            attributes: self.attributes | other.attributes,
+        }
+    }
+
+    /// Returns the shortest SGR sequence that turns the terminal's current style
+    /// `from` into `self`, or `None` if both are the same.
+    pub fn sgr_from(&self, from: &Style) -> Option<String> {
+        if self == from {
+            return None;
+        }
+
+        if *self == Style::default() {
+            return Some("\x1b[0m".to_string());
+        }
+
+        let mut codes = Vec::new();
+        let mut current = from.attributes;
+
+        for (attr, _, off) in Attributes::SGR {
+            if current.contains(attr) && !self.attributes.contains(attr) {
+                codes.push(off.to_string());
+                // 22 turns off both bold and dim.
+                if off == 22 {
+                    current.remove(Attributes::BOLD | Attributes::DIM);
+                } else {
+                    current.remove(attr);
+                }
+            }
+        }
+        for (attr, on, _) in Attributes::SGR {
+            if self.attributes.contains(attr) && !current.contains(attr) {
+                codes.push(on.to_string());
+            }
+        }
+
+        if self.fg != from.fg {
+            self.fg.push_sgr(&mut codes, false);
+        }
+        if self.bg != from.bg {
+            self.bg.push_sgr(&mut codes, true);
+        }
+
+        Some(format!("\x1b[{}m", codes.join(";")))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_attributes() {
+        let mut attrs = Attributes::BOLD | Attributes::UNDERLINE;
+        assert!(attrs.contains(Attributes::BOLD));
+        assert!(attrs.contains(Attributes::UNDERLINE));
+        assert!(!attrs.contains(Attributes::ITALIC));
+
+        attrs.remove(Attributes::BOLD);
+        assert!(!attrs.contains(Attributes::BOLD));
+
+        attrs.remove(Attributes::UNDERLINE);
+        assert!(attrs.is_empty());
+    }
+
+    #[test]
+    fn test_sgr_same_style() {
+        let style = Style::new().fg(Color::Red);
+        assert_eq!(style.sgr_from(&style), None);
+    }
+
+    #[test]
+    fn test_sgr_reset() {
+        let style = Style::new().fg(Color::Red).attributes(Attributes::BOLD);
+        assert_eq!(
+            Style::default().sgr_from(&style),
+            Some("\x1b[0m".to_string())
+        );
+    }
+
+    #[test]
+    fn test_sgr_colors() {
+        let from = Style::default();
+        assert_eq!(
+            Style::new().fg(Color::Red).sgr_from(&from),
+            Some("\x1b[31m".to_string())
+        );
+        assert_eq!(
+            Style::new().bg(Color::BrightBlue).sgr_from(&from),
+            Some("\x1b[104m".to_string())
+        );
+        assert_eq!(
+            Style::new().fg(Color::Indexed(208)).sgr_from(&from),
+            Some("\x1b[38;5;208m".to_string())
+        );
+        assert_eq!(
+            Style::new().bg(Color::Rgb(1, 2, 3)).sgr_from(&from),
+            Some("\x1b[48;2;1;2;3m".to_string())
+        );
+    }
+
+    #[test]
+    fn test_sgr_only_changes() {
+        let from = Style::new().fg(Color::Red).bg(Color::Blue);
+        let to = Style::new().fg(Color::Green).bg(Color::Blue);
+        assert_eq!(to.sgr_from(&from), Some("\x1b[32m".to_string()));
+
+        let to = Style::new()
+            .bg(Color::Blue)
+            .fg(Color::Red)
+            .attributes(Attributes::UNDERLINE);
+        assert_eq!(to.sgr_from(&from), Some("\x1b[4m".to_string()));
+    }
+
+    #[test]
+    fn test_sgr_remove_bold_keep_dim() {
+        let from = Style::new().attributes(Attributes::BOLD | Attributes::DIM | Attributes::ITALIC);
+        let to = Style::new().attributes(Attributes::DIM | Attributes::ITALIC);
+        assert_eq!(to.sgr_from(&from), Some("\x1b[22;2m".to_string()));
+    }
+
+    #[test]
+    fn test_attributes_replace_or_add() {
+        let style = Style::new()
+            .attributes(Attributes::BOLD)
+            .attributes(Attributes::DIM);
+        assert_eq!(style.attributes, Attributes::DIM);
+        let style = style.add_attributes(Attributes::ITALIC);
+        assert_eq!(style.attributes, Attributes::DIM | Attributes::ITALIC);
+    }
+
+    #[test]
+    fn test_patch() {
+        let base = Style::new().fg(Color::Red).bg(Color::Blue);
+        let span = Style::new().fg(Color::Green).attributes(Attributes::BOLD);
+        assert_eq!(
+            base.patch(span),
+            Style::new()
+                .fg(Color::Green)
+                .bg(Color::Blue)
+                .attributes(Attributes::BOLD)
+        );
+        assert_eq!(base.patch(Style::default()), base);
+    }
+}
@@ -1,16 +1,82 @@
 use std::{
     io::{self, Read, Write},
-    os::fd::AsRawFd,
+    os::fd::{AsRawFd, IntoRawFd, RawFd},
+    sync::{
+        atomic::{AtomicI32, AtomicUsize, Ordering},
+        OnceLock,
+    },
+    time::{Duration, Instant},
 };
 
-use nix::fcntl::{
-    fcntl,
-    FcntlArg::{F_GETFL, F_SETFL},
-    OFlag,
+use nix::{
+    errno::Errno,
+    fcntl::{
+        fcntl,
+        FcntlArg::{F_GETFL, F_SETFL},
+        OFlag,
+    },
+    libc,
+    sys::signal::{sigaction, SaFlags, SigAction, SigHandler, SigSet, Signal},
+    unistd,
 };
 use termios::Termios;
 
-use crate::Error;
+use crate::{event::MouseMode, input::InputParser, Error, Event, RawMode, Style, TerminalSession};
+
+nix::ioctl_read_bad!(tiocgwinsz, libc::TIOCGWINSZ, libc::winsize);
+
+/// Counts the `SIGWINCH` signals received since [`Terminal::watch_resize`] installed
+/// the handler. Every terminal remembers the count it has seen, so that each of
+/// them reports a resize even though the signal is delivered only once.
+static RESIZE_GENERATION: AtomicUsize = AtomicUsize::new(0);
+/// The write end of the self-pipe the handler wakes the main loop with, -1 until
+/// the handler is installed.
+static RESIZE_PIPE_WRITE: AtomicI32 = AtomicI32::new(-1);
+/// The read end of the self-pipe, or why the handler could not be installed.
+static RESIZE_PIPE_READ: OnceLock<Result<RawFd, Errno>> = OnceLock::new();
+
+extern "C" fn on_sigwinch(_: libc::c_int) {
+    // Only async-signal-safe calls are allowed here, which lock-free atomics and
+    // write are. If the pipe is full a wake up is already pending.
+    RESIZE_GENERATION.fetch_add(1, Ordering::Relaxed);
+    let fd = RESIZE_PIPE_WRITE.load(Ordering::Relaxed);
+    if fd >= 0 {
+        unsafe { libc::write(fd, [1_u8].as_ptr() as *const libc::c_void, 1) };
+    }
+}
+
+/// Creates the non-blocking self-pipe and installs the `SIGWINCH` handler,
+/// returning the read end of the pipe.
+fn install_sigwinch_handler() -> Result<RawFd, Errno> {
+    let (read, write) = unistd::pipe()?;
+    for fd in [read.as_raw_fd(), write.as_raw_fd()] {
+        let flags = OFlag::from_bits_truncate(fcntl(fd, F_GETFL)?);
+        fcntl(fd, F_SETFL(flags | OFlag::O_NONBLOCK))?;
+    }
+    RESIZE_PIPE_WRITE.store(write.into_raw_fd(), Ordering::Relaxed);
+
+    // SA_RESTART, so that the signal doesn't make unrelated system calls of the
+    // application fail with EINTR, the pipe wakes up the main loop instead.
+    let action = SigAction::new(
+        SigHandler::Handler(on_sigwinch),
+        SaFlags::SA_RESTART,
+        SigSet::empty(),
+    );
+    unsafe { sigaction(Signal::SIGWINCH, &action) }?;
+    Ok(read.into_raw_fd())
+}
+
+/// Queries the (width, height) of the terminal that `fd` refers to.
+pub fn terminal_size(fd: RawFd) -> Result<(usize, usize), Error> {
+    let mut size = libc::winsize {
+        ws_row: 0,
+        ws_col: 0,
+        ws_xpixel: 0,
+        ws_ypixel: 0,
+    };
+    unsafe { tiocgwinsz(fd, &mut size) }?;
+    Ok((size.ws_col as usize, size.ws_row as usize))
+}
 
 pub enum TerminalCommand<'a> {
     HideCursor,
@@ -20,6 +86,60 @@ pub enum TerminalCommand<'a> {
     PrintChar(char),
     ///(x, y)
     MoveCursorTo(usize, usize),
+    /// Switches the style used for the following characters, emitting only the
+    /// SGR codes that differ from the currently active style.
+    SetStyle(Style),
+    /// Enables mouse tracking in the given mode with SGR (1006) extended coordinates.
+    EnableMouse(MouseMode),
+    /// Disables all mouse tracking modes.
+    DisableMouse,
+    EnterAlternateScreen,
+    LeaveAlternateScreen,
+    /// Pasted text is then reported as a single [`Event::Paste`].
+    EnableBracketedPaste,
+    DisableBracketedPaste,
+    /// The terminal then reports [`Event::FocusGained`] and [`Event::FocusLost`].
+    EnableFocusReporting,
+    DisableFocusReporting,
+    EnableLineWrap,
+    DisableLineWrap,
+    SetCursorShape(CursorShape),
+    SaveCursor,
+    RestoreCursor,
+    /// Restricts scrolling to the rows from `top` to `bottom` (DECSTBM), 1-based and inclusive.
+    /// Fails with [`Error::ScrollRegionError`] unless `1 <= top < bottom <= height`.
+    SetScrollRegion(usize, usize),
+    ResetScrollRegion,
+    /// Sets the window title (OSC 0), control characters are left out so that
+    /// they can't end the sequence early.
+    SetTitle(&'a str),
+}
+
+/// The cursor shapes supported by DECSCUSR.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CursorShape {
+    /// The shape configured by the user in the terminal.
+    Default,
+    BlinkingBlock,
+    SteadyBlock,
+    BlinkingUnderline,
+    SteadyUnderline,
+    BlinkingBar,
+    SteadyBar,
+}
+
+impl CursorShape {
+    fn code(&self) -> u8 {
+        match self {
+            CursorShape::Default => 0,
+            CursorShape::BlinkingBlock => 1,
+            CursorShape::SteadyBlock => 2,
+            CursorShape::BlinkingUnderline => 3,
+            CursorShape::SteadyUnderline => 4,
+            CursorShape::BlinkingBar => 5,
+            CursorShape::SteadyBar => 6,
+        }
+    }
 }
 
 pub struct Terminal<T, U>
@@ -30,6 +150,20 @@ where
     width: usize,
     height: usize,
     local_buffer: String,
+    /// The style the terminal has, i.e. after everything written so far.
+    written_style: Style,
+    /// The style the buffered commands start from and the one they leave behind.
+    /// [`Terminal::exec_cmd`] may change the style before the buffer is flushed,
+    /// so the flush switches back to the start style first.
+    buffered_styles: (Style, Style),
+    parser: InputParser,
+    escape_timeout: Duration,
+    pending_since: Option<Instant>,
+    /// The [`RESIZE_GENERATION`] last reported, `None` unless resizes are watched.
+    resize_generation: Option<usize>,
+    /// The settings of stdin before the first [`Terminal::set_raw_mode`], restored by
+    /// [`Terminal::raw_mode`] with `false`.
+    original_termios: Option<Termios>,
     stdin: T,
     stdout: U,
 }
@@ -44,6 +178,13 @@ where
             width,
             height,
             local_buffer: String::new(),
+            written_style: Style::default(),
+            buffered_styles: (Style::default(), Style::default()),
+            parser: InputParser::new(),
+            escape_timeout: Duration::from_millis(50),
+            pending_since: None,
+            resize_generation: None,
+            original_termios: None,
             stdin,
             stdout,
         }
@@ -63,18 +204,175 @@ where
         }
     }
 
-    pub fn raw_mode(&mut self, enable: bool) -> Result<(), Error> {
-        let mut termios = Termios::from_fd(self.stdin.as_raw_fd())?;
+    /// Updates the dimensions used to validate cursor movements.
+    pub fn resize(&mut self, width: usize, height: usize) {
+        self.width = width;
+        self.height = height;
+    }
+
+    /// Queries the current (width, height) of the terminal using `TIOCGWINSZ` on stdout.
+    pub fn size(&self) -> Result<(usize, usize), Error> {
+        terminal_size(self.stdout.as_raw_fd())
+    }
+
+    /// Installs a `SIGWINCH` handler so that [`Terminal::read_event`] reports
+    /// [`Event::Resize`] whenever the terminal window changes size.
+    ///
+    /// The handler writes to a non-blocking self-pipe, see [`Terminal::resize_fd`],
+    /// so that a main loop waiting in `poll` or `select` on stdin and the pipe
+    /// wakes up, and `read_event` drains it. The handler is process wide and is
+    /// installed only once, any number of terminals can watch for resizes.
+    pub fn watch_resize(&mut self) -> Result<(), Error> {
+        // A failed install is remembered, so that later calls fail the same way.
+        (*RESIZE_PIPE_READ.get_or_init(install_sigwinch_handler))?;
+
+        if self.resize_generation.is_none() {
+            self.resize_generation = Some(RESIZE_GENERATION.load(Ordering::Relaxed));
+        }
+        Ok(())
+    }
+
+    /// The read end of the resize self-pipe, which becomes readable when the
+    /// terminal is resized. `None` until [`Terminal::watch_resize`] is called.
+    pub fn resize_fd(&self) -> Option<RawFd> {
+        self.resize_generation?;
+        RESIZE_PIPE_READ.get()?.ok()
+    }
 
+    /// Drains the resize self-pipe, returns true if a `SIGWINCH` arrived since the last call.
+    fn take_resize(&mut self) -> bool {
+        let Some(fd) = self.resize_fd() else {
+            return false;
+        };
+        let mut buf = [0_u8; 64];
+        while let Ok(n) = unistd::read(fd, &mut buf) {
+            if n == 0 {
+                break;
+            }
+        }
+
+        let Some(seen) = self.resize_generation.as_mut() else {
+            return false;
+        };
+        let current = RESIZE_GENERATION.load(Ordering::Relaxed);
+        if *seen == current {
+            return false;
+        }
+        *seen = current;
+        true
+    }
+
+    fn resize_event(&mut self) -> Result<Event, Error> {
+        let (width, height) = self.size()?;
+        self.resize(width, height);
+        Ok(Event::Resize(width, height))
+    }
+
+    /// Sets how long [`Terminal::read_event`] waits for the rest of an escape
+    /// sequence before reporting a lone `ESC` key press. Defaults to 50ms.
+    pub fn set_escape_timeout(&mut self, timeout: Duration) {
+        self.escape_timeout = timeout;
+    }
+
+    /// Reads the available input and decodes it into an [`Event`].
+    ///
+    /// If [`Terminal::watch_resize`] was called, a pending terminal resize is
+    /// reported as [`Event::Resize`] before any input.
+    ///
+    /// Returns `Ok(None)` if no complete event is available yet, which is the case
+    /// when stdin is in non-blocking mode and nothing was typed. Sequences split
+    /// across reads are kept until the rest arrives. A lone `ESC` is only reported
+    /// once the escape timeout has elapsed without more input, so this should be
+    /// called repeatedly from the main loop with stdin in non-blocking mode.
+    ///
+    /// Once stdin is closed, what is left of an incomplete sequence is flushed and
+    /// then an [`Error::IoError`] of kind [`io::ErrorKind::UnexpectedEof`] is returned.
+    pub fn read_event(&mut self) -> Result<Option<Event>, Error> {
+        if self.take_resize() {
+            return self.resize_event().map(Some);
+        }
+
+        if let Some(event) = self.parser.next_event() {
+            return Ok(Some(event));
+        }
+
+        let mut buf = [0_u8; 1024];
+        match self.stdin.read(&mut buf) {
+            Ok(n) if n > 0 => {
+                self.parser.push(&buf[..n]);
+                // The timeout counts from the last byte received, so that
+                // large pastes arriving over multiple reads are not cut.
+                self.pending_since = None;
+            }
+            Ok(_) => {
+                if let Some(event) = self.parser.next_event().or_else(|| self.parser.flush()) {
+                    return Ok(Some(event));
+                }
+                if !self.parser.has_pending() {
+                    return Err(Error::IoError(io::Error::new(
+                        io::ErrorKind::UnexpectedEof,
+                        "End of input or no more data available.",
+                    )));
+                }
+            }
+            Err(err) if err.kind() == io::ErrorKind::WouldBlock => {}
+            Err(err) if err.kind() == io::ErrorKind::Interrupted => {}
+            Err(err) => return Err(Error::IoError(err)),
+        }
+
+        if self.take_resize() {
+            return self.resize_event().map(Some);
+        }
+
+        if let Some(event) = self.parser.next_event() {
+            self.pending_since = None;
+            return Ok(Some(event));
+        }
+
+        if !self.parser.has_pending() {
+            self.pending_since = None;
+            return Ok(None);
+        }
+
+        let since = *self.pending_since.get_or_insert_with(Instant::now);
+        if since.elapsed() >= self.escape_timeout {
+            self.pending_since = None;
+            return Ok(self.parser.flush());
+        }
+
+        Ok(None)
+    }
+
+    /// Starts a [`TerminalSession`] on this terminal's stdin and stdout, which
+    /// restores the terminal state once dropped.
+    pub fn session(&self) -> Result<TerminalSession, Error> {
+        TerminalSession::enter(&self.stdin, &self.stdout)
+    }
+
+    /// Starts a [`TerminalSession`] like [`Terminal::session`] with the given [`RawMode`].
+    pub fn session_with(&self, mode: RawMode) -> Result<TerminalSession, Error> {
+        TerminalSession::enter_with(&self.stdin, &self.stdout, mode)
+    }
+
+    /// Enables [`RawMode::cbreak`], or restores the settings stdin had before raw
+    /// mode was first enabled if `enable` is false. Use `set_raw_mode(RawMode::cooked())`
+    /// to force the usual cooked settings instead.
+    pub fn raw_mode(&mut self, enable: bool) -> Result<(), Error> {
         if enable {
-            termios.c_lflag &= !termios::ECHO;
-            termios.c_lflag &= !termios::ICANON;
-        } else {
-            termios.c_lflag |= termios::ECHO;
-            termios.c_lflag |= termios::ICANON;
+            return self.set_raw_mode(RawMode::cbreak());
         }
+        if let Some(original) = self.original_termios.take() {
+            termios::tcsetattr(self.stdin.as_raw_fd(), termios::TCSANOW, &original)?;
+        }
+        Ok(())
+    }
 
-        termios::tcsetattr(self.stdin.as_raw_fd(), termios::TCSANOW, &mut termios)?;
+    /// Applies the given [`RawMode`] to stdin.
+    pub fn set_raw_mode(&mut self, mode: RawMode) -> Result<(), Error> {
+        let mut termios = Termios::from_fd(self.stdin.as_raw_fd())?;
+        self.original_termios.get_or_insert(termios);
+        mode.apply(&mut termios);
+        termios::tcsetattr(self.stdin.as_raw_fd(), termios::TCSANOW, &termios)?;
 
         Ok(())
     }
@@ -85,20 +383,37 @@ where
         let stdin_fd = self.stdin.as_raw_fd();
         let curr_flags = fcntl(stdin_fd, F_GETFL)?;
 
-        let new_flags: OFlag;
-        if enable {
-            new_flags = OFlag::from_bits_truncate(curr_flags) | OFlag::O_NONBLOCK;
+        let new_flags = if enable {
+            OFlag::from_bits_truncate(curr_flags) | OFlag::O_NONBLOCK
         } else {
-            new_flags = OFlag::from_bits_truncate(curr_flags) & !OFlag::O_NONBLOCK;
-        }
+            OFlag::from_bits_truncate(curr_flags) & !OFlag::O_NONBLOCK
+        };
 
         fcntl(stdin_fd, F_SETFL(new_flags))?;
 
         Ok(())
     }
 
+    /// Enables mouse reporting, mouse input is then returned by [`Terminal::read_event`]
+    /// as [`Event::Mouse`].
+    pub fn enable_mouse(&mut self, mode: MouseMode) -> Result<(), Error> {
+        self.exec_cmd(TerminalCommand::EnableMouse(mode))
+    }
+
+    /// Disables mouse reporting, see [`Terminal::enable_mouse`].
+    pub fn disable_mouse(&mut self) -> Result<(), Error> {
+        self.exec_cmd(TerminalCommand::DisableMouse)
+    }
+
     /// Flushes the current buffer to the stdout of the process.
     pub fn flush(&mut self) -> Result<(), Error> {
+        let (start, end) = self.buffered_styles;
+        if !self.local_buffer.is_empty() {
+            if let Some(sgr) = start.sgr_from(&self.written_style) {
+                self.stdout.write_all(sgr.as_bytes())?;
+            }
+            self.written_style = end;
+        }
         write!(self.stdout, "{}", self.local_buffer)?;
         self.stdout.flush()?;
         self.local_buffer.clear();
@@ -106,39 +421,330 @@ where
     }
 
     /// Immediately executes a given ANSI command, without modifying the display buffer.
-    pub fn exec_cmd(&self, command: TerminalCommand) -> Result<(), Error> {
-        match command {
-            TerminalCommand::HideCursor => todo!(),
-            TerminalCommand::UnhideCursor => todo!(),
-            TerminalCommand::ClearScreen => todo!(),
-            TerminalCommand::PrintStr(_) => todo!(),
-            TerminalCommand::PrintChar(_) => todo!(),
-            TerminalCommand::MoveCursorTo(_, _) => todo!(),
-        };
-        // Ok(())
+    pub fn exec_cmd(&mut self, command: TerminalCommand) -> Result<(), Error> {
+        let mut out = String::new();
+        encode_cmd(
+            command,
+            &mut out,
+            self.width,
+            self.height,
+            &mut self.written_style,
+        )?;
+        self.stdout.write_all(out.as_bytes())?;
+        self.stdout.flush()?;
+        Ok(())
     }
 
     /// Adds a given ANSI command to the screen buffer to be executed later.
     pub fn buffer_cmd(&mut self, command: TerminalCommand) -> Result<(), Error> {
-        match command {
-            TerminalCommand::HideCursor => self.local_buffer.push_str("\x1b[?25l"),
-            TerminalCommand::UnhideCursor => self.local_buffer.push_str("\x1b[?25h"),
-            TerminalCommand::ClearScreen => self.local_buffer.push_str("\x1b[2J"),
-            TerminalCommand::PrintStr(text) => self.local_buffer.push_str(text),
-            TerminalCommand::PrintChar(ch) => self.local_buffer.push(ch),
-            TerminalCommand::MoveCursorTo(x, y) => {
-                if x > self.width || y > self.height {
-                    return Err(Error::PositionError {
-                        x,
-                        y,
-                        width: self.width,
-                        height: self.height,
-                    });
-                };
-                self.local_buffer
-                    .push_str(format!("\x1b[{};{}H", y, x).as_str())
+        if self.local_buffer.is_empty() {
+            self.buffered_styles = (self.written_style, self.written_style);
+        }
+        encode_cmd(
+            command,
+            &mut self.local_buffer,
+            self.width,
+            self.height,
+            &mut self.buffered_styles.1,
+        )
+    }
+}
+
+/// Appends the escape sequence of `command` to `out`, shared by [`Terminal::buffer_cmd`]
+/// and [`Terminal::exec_cmd`] so that both always produce the same bytes.
+/// `current_style` is the style active where `out` ends, updated by [`TerminalCommand::SetStyle`].
+fn encode_cmd(
+    command: TerminalCommand,
+    out: &mut String,
+    width: usize,
+    height: usize,
+    current_style: &mut Style,
+) -> Result<(), Error> {
+    match command {
+        TerminalCommand::HideCursor => out.push_str("\x1b[?25l"),
+        TerminalCommand::UnhideCursor => out.push_str("\x1b[?25h"),
+        TerminalCommand::ClearScreen => out.push_str("\x1b[2J"),
+        TerminalCommand::PrintStr(text) => out.push_str(text),
+        TerminalCommand::PrintChar(ch) => out.push(ch),
+        TerminalCommand::MoveCursorTo(x, y) => {
+            if x > width || y > height {
+                return Err(Error::PositionError {
+                    x,
+                    y,
+                    width,
+                    height,
+                });
+            };
+            out.push_str(format!("\x1b[{};{}H", y, x).as_str())
+        }
+        TerminalCommand::SetStyle(style) => {
+            if let Some(sgr) = style.sgr_from(current_style) {
+                out.push_str(&sgr);
+                *current_style = style;
+            }
+        }
+        TerminalCommand::EnableMouse(mode) => {
+            out.push_str(format!("\x1b[?{}h\x1b[?1006h", mode.code()).as_str())
+        }
+        TerminalCommand::DisableMouse => {
+            out.push_str("\x1b[?1006l\x1b[?1003l\x1b[?1002l\x1b[?1000l\x1b[?9l")
+        }
+        TerminalCommand::EnterAlternateScreen => out.push_str("\x1b[?1049h"),
+        TerminalCommand::LeaveAlternateScreen => out.push_str("\x1b[?1049l"),
+        TerminalCommand::EnableBracketedPaste => out.push_str("\x1b[?2004h"),
+        TerminalCommand::DisableBracketedPaste => out.push_str("\x1b[?2004l"),
+        TerminalCommand::EnableFocusReporting => out.push_str("\x1b[?1004h"),
+        TerminalCommand::DisableFocusReporting => out.push_str("\x1b[?1004l"),
+        TerminalCommand::EnableLineWrap => out.push_str("\x1b[?7h"),
+        TerminalCommand::DisableLineWrap => out.push_str("\x1b[?7l"),
+        TerminalCommand::SetCursorShape(shape) => {
+            out.push_str(format!("\x1b[{} q", shape.code()).as_str())
+        }
+        TerminalCommand::SaveCursor => out.push_str("\x1b7"),
+        TerminalCommand::RestoreCursor => out.push_str("\x1b8"),
+        TerminalCommand::SetScrollRegion(top, bottom) => {
+            if top == 0 || top >= bottom || bottom > height {
+                return Err(Error::ScrollRegionError {
+                    top,
+                    bottom,
+                    height,
+                });
             }
+            out.push_str(format!("\x1b[{};{}r", top, bottom).as_str())
+        }
+        TerminalCommand::ResetScrollRegion => out.push_str("\x1b[r"),
+        TerminalCommand::SetTitle(title) => {
+            out.push_str("\x1b]0;");
+            out.extend(title.chars().filter(|ch| !ch.is_control()));
+            out.push('\x07');
+        }
+    };
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{testing::MockOutput, Color, KeyCode, KeyModifiers};
+    use std::fs::File;
+
+    fn mock_terminal(width: usize, height: usize) -> (Terminal<File, MockOutput>, MockOutput) {
+        let output = MockOutput::default();
+        let stdin = File::open("/dev/null").unwrap();
+        (Terminal::new(width, height, stdin, output.clone()), output)
+    }
+
+    fn all_commands() -> Vec<TerminalCommand<'static>> {
+        vec![
+            TerminalCommand::HideCursor,
+            TerminalCommand::UnhideCursor,
+            TerminalCommand::ClearScreen,
+            TerminalCommand::PrintStr("hello"),
+            TerminalCommand::PrintChar('x'),
+            TerminalCommand::MoveCursorTo(3, 4),
+            TerminalCommand::SetStyle(Style::new().fg(Color::Red)),
+            TerminalCommand::SetStyle(Style::default()),
+            TerminalCommand::EnableMouse(MouseMode::ButtonEvent),
+            TerminalCommand::DisableMouse,
+            TerminalCommand::EnterAlternateScreen,
+            TerminalCommand::LeaveAlternateScreen,
+            TerminalCommand::EnableBracketedPaste,
+            TerminalCommand::DisableBracketedPaste,
+            TerminalCommand::EnableFocusReporting,
+            TerminalCommand::DisableFocusReporting,
+            TerminalCommand::EnableLineWrap,
+            TerminalCommand::DisableLineWrap,
+            TerminalCommand::SetCursorShape(CursorShape::SteadyBar),
+            TerminalCommand::SaveCursor,
+            TerminalCommand::RestoreCursor,
+            TerminalCommand::SetScrollRegion(2, 5),
+            TerminalCommand::ResetScrollRegion,
+            TerminalCommand::SetTitle("title"),
+        ]
+    }
+
+    #[test]
+    fn test_exec_cmd_writes_immediately() -> Result<(), Error> {
+        let (mut terminal, output) = mock_terminal(10, 10);
+        terminal.buffer_cmd(TerminalCommand::PrintStr("buffered"))?;
+        terminal.exec_cmd(TerminalCommand::MoveCursorTo(2, 3))?;
+
+        assert_eq!(output.take(), "\x1b[3;2H");
+        assert_eq!(terminal.local_buffer, "buffered");
+        Ok(())
+    }
+
+    #[test]
+    fn test_exec_and_buffer_cmd_match() -> Result<(), Error> {
+        let (mut exec_terminal, exec_output) = mock_terminal(10, 10);
+        for command in all_commands() {
+            exec_terminal.exec_cmd(command)?;
+        }
+
+        let (mut buffer_terminal, buffer_output) = mock_terminal(10, 10);
+        for command in all_commands() {
+            buffer_terminal.buffer_cmd(command)?;
+        }
+        assert_eq!(buffer_output.take(), "");
+        buffer_terminal.flush()?;
+
+        assert_eq!(exec_output.take(), buffer_output.take());
+        Ok(())
+    }
+
+    #[test]
+    fn test_exec_cmd_sequences() -> Result<(), Error> {
+        let (mut terminal, output) = mock_terminal(10, 10);
+
+        terminal.exec_cmd(TerminalCommand::EnterAlternateScreen)?;
+        assert_eq!(output.take(), "\x1b[?1049h");
+        terminal.exec_cmd(TerminalCommand::SetCursorShape(
+            CursorShape::BlinkingUnderline,
+        ))?;
+        assert_eq!(output.take(), "\x1b[3 q");
+        terminal.exec_cmd(TerminalCommand::SetScrollRegion(2, 8))?;
+        assert_eq!(output.take(), "\x1b[2;8r");
+        terminal.exec_cmd(TerminalCommand::SetTitle("rectext"))?;
+        assert_eq!(output.take(), "\x1b]0;rectext\x07");
+        terminal.exec_cmd(TerminalCommand::EnableMouse(MouseMode::AnyEvent))?;
+        assert_eq!(output.take(), "\x1b[?1003h\x1b[?1006h");
+        Ok(())
+    }
+
+    #[test]
+    fn test_exec_cmd_style_is_shared() -> Result<(), Error> {
+        let (mut terminal, output) = mock_terminal(10, 10);
+        let red = Style::new().fg(Color::Red);
+
+        terminal.exec_cmd(TerminalCommand::SetStyle(red))?;
+        assert_eq!(output.take(), "\x1b[31m");
+        terminal.buffer_cmd(TerminalCommand::SetStyle(red))?;
+        assert_eq!(terminal.local_buffer, "");
+        Ok(())
+    }
+
+    #[test]
+    fn test_exec_cmd_between_buffered_styles() -> Result<(), Error> {
+        let (mut terminal, output) = mock_terminal(10, 10);
+        let red = Style::new().fg(Color::Red);
+        let blue = Style::new().fg(Color::Blue);
+
+        terminal.buffer_cmd(TerminalCommand::SetStyle(red))?;
+        terminal.buffer_cmd(TerminalCommand::PrintChar('a'))?;
+        // The terminal hasn't turned red yet, so the diff starts from the default.
+        terminal.exec_cmd(TerminalCommand::SetStyle(blue))?;
+        assert_eq!(output.take(), "\x1b[34m");
+
+        // The buffered commands start from the default style again.
+        terminal.flush()?;
+        assert_eq!(output.take(), "\x1b[0m\x1b[31ma");
+        terminal.exec_cmd(TerminalCommand::SetStyle(red))?;
+        assert_eq!(output.take(), "");
+        Ok(())
+    }
+
+    #[test]
+    fn test_exec_cmd_position_error() {
+        let (mut terminal, output) = mock_terminal(10, 10);
+        assert!(terminal
+            .exec_cmd(TerminalCommand::MoveCursorTo(11, 1))
+            .is_err());
+        assert_eq!(output.take(), "");
+    }
+
+    #[test]
+    fn test_exec_cmd_scroll_region_error() -> Result<(), Error> {
+        let (mut terminal, output) = mock_terminal(10, 10);
+        for (top, bottom) in [(0, 5), (5, 5), (6, 5), (2, 11)] {
+            assert!(matches!(
+                terminal.exec_cmd(TerminalCommand::SetScrollRegion(top, bottom)),
+                Err(Error::ScrollRegionError { height: 10, .. })
+            ));
+        }
+        assert_eq!(output.take(), "");
+        terminal.exec_cmd(TerminalCommand::SetScrollRegion(1, 10))?;
+        assert_eq!(output.take(), "\x1b[1;10r");
+        Ok(())
+    }
+
+    #[test]
+    fn test_exec_cmd_title_drops_control_characters() -> Result<(), Error> {
+        let (mut terminal, output) = mock_terminal(10, 10);
+        terminal.exec_cmd(TerminalCommand::SetTitle("a\x07b\x1b]0;c\u{9c}\n"))?;
+        assert_eq!(output.take(), "\x1b]0;ab]0;c\x07");
+        Ok(())
+    }
+
+    #[test]
+    fn test_raw_mode_restores_original_settings() -> Result<(), Error> {
+        let pty = nix::pty::openpty(None, None)?;
+        let stdin = File::from(pty.slave.try_clone()?);
+        let mut terminal = Terminal::new(10, 10, stdin, MockOutput::default());
+        let settings = || Termios::from_fd(pty.slave.as_raw_fd());
+
+        // Settings that differ from what `RawMode::cooked` would produce.
+        let mut original = settings()?;
+        original.c_cc[termios::VTIME] = 5;
+        termios::tcsetattr(pty.slave.as_raw_fd(), termios::TCSANOW, &original)?;
+
+        terminal.raw_mode(true)?;
+        assert_eq!(settings()?.c_lflag & termios::ICANON, 0);
+        terminal.set_raw_mode(RawMode::raw())?;
+        terminal.raw_mode(false)?;
+        assert_eq!(settings()?, original);
+
+        // Nothing to restore anymore.
+        terminal.raw_mode(false)?;
+        assert_eq!(settings()?, original);
+        Ok(())
+    }
+
+    #[test]
+    fn test_read_event_reports_eof() -> Result<(), Error> {
+        let (read, write) = nix::unistd::pipe()?;
+        nix::unistd::write(&write, b"a\x1b")?;
+        drop(write);
+        let mut terminal = Terminal::new(10, 10, File::from(read), MockOutput::default());
+
+        let key = |code| Some(Event::key(code, KeyModifiers::NONE));
+        assert_eq!(terminal.read_event()?, key(KeyCode::Char('a')));
+        // The lone ESC doesn't wait for the timeout, no more input can follow.
+        assert_eq!(terminal.read_event()?, key(KeyCode::Esc));
+        assert!(matches!(
+            terminal.read_event(),
+            Err(Error::IoError(err)) if err.kind() == io::ErrorKind::UnexpectedEof
+        ));
+        Ok(())
+    }
+
+    #[test]
+    fn test_watch_resize() -> Result<(), Error> {
+        let null_terminal = || -> Result<_, Error> {
+            let stdin = File::open("/dev/null")?;
+            let stdout = File::create("/dev/null")?;
+            Ok(Terminal::new(10, 10, stdin, stdout))
+        };
+        let mut terminal = null_terminal()?;
+        let mut other = null_terminal()?;
+
+        assert!(!terminal.take_resize());
+        assert_eq!(terminal.resize_fd(), None);
+        terminal.watch_resize()?;
+        other.watch_resize()?;
+        nix::sys::signal::raise(Signal::SIGWINCH)?;
+
+        // The pipe wakes up a main loop waiting on it.
+        let mut poll_fd = libc::pollfd {
+            fd: terminal.resize_fd().unwrap(),
+            events: libc::POLLIN,
+            revents: 0,
         };
+        assert_eq!(unsafe { libc::poll(&mut poll_fd, 1, 0) }, 1);
+        // Every watching terminal sees the resize once.
+        assert!(terminal.take_resize());
+        assert!(!terminal.take_resize());
+        assert!(other.take_resize());
+        assert!(!other.take_resize());
         Ok(())
     }
 }
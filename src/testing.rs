@@ -0,0 +1,33 @@
+use std::{
+    cell::RefCell,
+    io::{self, Write},
+    os::fd::{AsRawFd, RawFd},
+    rc::Rc,
+};
+
+/// An in-memory stdout that can still be inspected after being moved into a `Terminal`.
+#[derive(Clone, Default)]
+pub struct MockOutput(Rc<RefCell<Vec<u8>>>);
+
+impl MockOutput {
+    /// Returns everything written so far and clears it.
+    pub fn take(&self) -> String {
+        String::from_utf8(self.0.borrow_mut().drain(..).collect()).unwrap()
+    }
+}
+
+impl Write for MockOutput {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.0.borrow_mut().extend_from_slice(buf);
+        Ok(buf.len())
+    }
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+impl AsRawFd for MockOutput {
+    fn as_raw_fd(&self) -> RawFd {
+        -1
+    }
+}
@@ -1,11 +1,75 @@
 use std::{cell::RefCell, rc::Rc};
 
-use crate::{Buffer, Error};
+use crate::{Buffer, Error, Event, Rect};
 
 pub trait UIElement {
     fn draw(&self, buffer: &mut Buffer) -> Result<(), Error>;
+    /// The position of the top left corner, relative to the parent element, or to
+    /// the screen for top level elements.
     fn get_position(&self) -> (usize, usize);
     fn set_position(&mut self, x: usize, y: usize);
-    fn add_sub_element(&mut self, id: String, element: Rc<RefCell<dyn UIElement>>);
-    fn remove_sub_element(&mut self, id: &str);
+    /// The (width, height) of the element. Elements without a size of their own
+    /// are as large as their [`UIElement::preferred_size`].
+    fn get_size(&self) -> (usize, usize) {
+        self.preferred_size()
+    }
+    /// Resizes the element, used by layouts. Elements without a size ignore it.
+    fn set_size(&mut self, _width: usize, _height: usize) {}
+    /// The area covered by the element.
+    fn bounds(&self) -> Rect {
+        let (x, y) = self.get_position();
+        let (width, height) = self.get_size();
+        Rect::new(x, y, width, height)
+    }
+    /// The (width, height) the element would like to have, used by [`crate::Flex`] layouts.
+    fn preferred_size(&self) -> (usize, usize) {
+        (0, 0)
+    }
+    /// The smallest (width, height) the element can be shrunk to by a layout.
+    fn min_size(&self) -> (usize, usize) {
+        self.preferred_size()
+    }
+    /// Adds a sub element on top of the others. Elements that can't have sub
+    /// elements return [`Error::NotAContainer`], ids containing
+    /// [`crate::tree::PATH_SEPARATOR`] are rejected with [`Error::InvalidId`].
+    fn add_sub_element(
+        &mut self,
+        _id: String,
+        _element: Rc<RefCell<dyn UIElement>>,
+    ) -> Result<(), Error> {
+        Err(Error::NotAContainer)
+    }
+    /// Removes a sub element, if there is one with the given id. Elements that
+    /// can't have sub elements return [`Error::NotAContainer`].
+    fn remove_sub_element(&mut self, _id: &str) -> Result<(), Error> {
+        Err(Error::NotAContainer)
+    }
+    /// The direct sub element with the given id, if any.
+    fn get_sub_element(&self, _id: &str) -> Option<Rc<RefCell<dyn UIElement>>> {
+        None
+    }
+    /// The ids of the direct sub elements, in drawing order.
+    fn sub_element_ids(&self) -> Vec<String> {
+        Vec::new()
+    }
+    /// How far the sub elements are scrolled, as (x, y), used to find the element under the mouse.
+    /// Negative when the sub elements start inside of the element, e.g. within a border.
+    fn content_offset(&self) -> (isize, isize) {
+        (0, 0)
+    }
+    /// Whether the element can get the focus, e.g. with Tab.
+    fn is_focusable(&self) -> bool {
+        false
+    }
+    /// Called when the element gets or loses the focus, focused elements
+    /// usually draw themselves highlighted.
+    fn set_focused(&mut self, _focused: bool) {}
+    /// Called when the mouse pointer enters or leaves the element.
+    fn set_hovered(&mut self, _hovered: bool) {}
+    /// Handles an input event and returns whether it was used. Events the
+    /// element doesn't use bubble up to its parents. Mouse positions are
+    /// relative to the element.
+    fn handle_event(&mut self, _event: &Event) -> bool {
+        false
+    }
 }
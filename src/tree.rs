@@ -0,0 +1,117 @@
+use std::{cell::RefCell, rc::Rc};
+
+use crate::{Error, UIElement};
+
+/// Separates the ids of the elements on a path, e.g. `"panel/header/title"`.
+pub const PATH_SEPARATOR: char = '/';
+
+/// Rejects ids that could not be told apart from a path.
+pub(crate) fn check_id(id: &str) -> Result<(), Error> {
+    if id.contains(PATH_SEPARATOR) {
+        return Err(Error::InvalidId(id.to_string()));
+    }
+    Ok(())
+}
+
+type Node = (String, Rc<RefCell<dyn UIElement>>);
+
+/// Follows `path` from `root` down through the sub elements.
+pub(crate) fn descend(
+    root: Rc<RefCell<dyn UIElement>>,
+    path: &str,
+) -> Option<Rc<RefCell<dyn UIElement>>> {
+    let mut element = root;
+    for id in path.split(PATH_SEPARATOR) {
+        let next = element.borrow().get_sub_element(id)?;
+        element = next;
+    }
+    Some(element)
+}
+
+/// Finds the topmost element containing (x, y), searching `nodes` from the last
+/// drawn one and descending into sub elements, which are only found within the
+/// bounds of their parent. (x, y) is relative to the parent of `nodes`.
+/// Returns the path of the element and the point relative to it.
+pub(crate) fn hit_test(nodes: &[Node], x: usize, y: usize) -> Option<(String, usize, usize)> {
+    nodes.iter().rev().find_map(|(path, element)| {
+        let bounds = element.borrow().bounds();
+        if !bounds.contains(x, y) {
+            return None;
+        }
+        let (x, y) = (x - bounds.x, y - bounds.y);
+        let (offset_x, offset_y) = element.borrow().content_offset();
+        x.checked_add_signed(offset_x)
+            .zip(y.checked_add_signed(offset_y))
+            .and_then(|(inner_x, inner_y)| hit_test(&children(path, element), inner_x, inner_y))
+            .or_else(|| Some((path.clone(), x, y)))
+    })
+}
+
+fn children(path: &str, element: &Rc<RefCell<dyn UIElement>>) -> Vec<Node> {
+    let element = element.borrow();
+    element
+        .sub_element_ids()
+        .into_iter()
+        .filter_map(|id| {
+            let child = element.get_sub_element(&id)?;
+            Some((format!("{path}{PATH_SEPARATOR}{id}"), child))
+        })
+        .collect()
+}
+
+/// Visits every element before its sub elements, yielding (path, element).
+/// Siblings are visited in drawing order.
+pub struct PreOrder {
+    stack: Vec<Node>,
+}
+
+impl PreOrder {
+    pub(crate) fn new(roots: Vec<Node>) -> Self {
+        let mut stack = roots;
+        stack.reverse();
+        Self { stack }
+    }
+}
+
+impl Iterator for PreOrder {
+    type Item = Node;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let (path, element) = self.stack.pop()?;
+        self.stack
+            .extend(children(&path, &element).into_iter().rev());
+        Some((path, element))
+    }
+}
+
+/// Visits every element after its sub elements, yielding (path, element).
+/// Siblings are visited in drawing order.
+pub struct PostOrder {
+    /// Nodes with whether their sub elements are already on the stack.
+    stack: Vec<(Node, bool)>,
+}
+
+impl PostOrder {
+    pub(crate) fn new(roots: Vec<Node>) -> Self {
+        Self {
+            stack: roots.into_iter().rev().map(|node| (node, false)).collect(),
+        }
+    }
+}
+
+impl Iterator for PostOrder {
+    type Item = Node;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let ((path, element), expanded) = self.stack.pop()?;
+            if expanded {
+                return Some((path, element));
+            }
+            let children = children(&path, &element);
+            self.stack.push(((path, element), true));
+            self.stack
+                .extend(children.into_iter().rev().map(|node| (node, false)));
+        }
+    }
+}